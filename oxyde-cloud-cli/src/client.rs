@@ -0,0 +1,27 @@
+use crate::api_key::api_key;
+use anyhow::{Context, Result};
+use oxyde_cloud_client::{Client, ClientConfig};
+
+/// Creates an API client authenticated with the stored API key.
+pub fn client() -> Result<Client> {
+    let api_key = api_key().context("Failed to get API key")?;
+    client_with_api_key(api_key)
+}
+
+/// Creates an API client that identifies as this CLI. The API URL can be overridden with the
+/// `OXYDE_CLOUD_API_URL` environment variable.
+pub fn client_with_api_key(api_key: String) -> Result<Client> {
+    client_with_config(ClientConfig::from_env(), api_key)
+}
+
+/// Creates an API client that identifies as this CLI from `config`, e.g. to talk to another API.
+pub fn client_with_config(config: ClientConfig, api_key: String) -> Result<Client> {
+    config
+        .user_agent(format!(
+            "oxy/{} {}",
+            env!("CARGO_PKG_VERSION"),
+            ClientConfig::DEFAULT_USER_AGENT
+        ))
+        .build(api_key)
+        .context("Failed to create API client")
+}
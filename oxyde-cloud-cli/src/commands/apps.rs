@@ -0,0 +1,193 @@
+use crate::client::client;
+use crate::table::Table;
+use anyhow::{Context, Result, bail};
+use clap::Subcommand;
+use cliclack::{input, intro, log::warning, outro, outro_cancel};
+use oxyde_cloud_common::config::AppConfig;
+use oxyde_cloud_common::net::DomainStatus;
+use std::path::PathBuf;
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum AppsCommand {
+    /// List the apps of all your teams
+    List {
+        /// Only list the apps of this team
+        #[arg(short, long)]
+        team: Option<String>,
+    },
+
+    /// Show the details of an app
+    Info {
+        /// The name of the app. Defaults to the name from the config in the current directory
+        #[arg(short, long)]
+        name: Option<String>,
+
+        /// Sets a custom config file. Defaults to `oxyde-cloud.toml`
+        #[arg(short, long, value_name = "FILE", default_value = "oxyde-cloud.toml")]
+        config: PathBuf,
+    },
+
+    /// Delete an app with all its deployments, logs and secrets
+    Delete {
+        /// The name of the app. Defaults to the name from the config in the current directory
+        #[arg(short, long)]
+        name: Option<String>,
+
+        /// Sets a custom config file. Defaults to `oxyde-cloud.toml`
+        #[arg(short, long, value_name = "FILE", default_value = "oxyde-cloud.toml")]
+        config: PathBuf,
+
+        /// Skip the confirmation prompt by passing the slug of the app
+        #[arg(long, value_name = "SLUG")]
+        confirm: Option<String>,
+    },
+
+    /// Move an app to another team
+    Transfer {
+        /// The slug of the team to move the app to
+        #[arg(long, value_name = "TEAM")]
+        to: String,
+
+        /// The name of the app. Defaults to the name from the config in the current directory
+        #[arg(short, long)]
+        name: Option<String>,
+
+        /// Sets a custom config file. Defaults to `oxyde-cloud.toml`
+        #[arg(short, long, value_name = "FILE", default_value = "oxyde-cloud.toml")]
+        config: PathBuf,
+
+        /// Skip the confirmation prompt by passing the slug of the app
+        #[arg(long, value_name = "SLUG")]
+        confirm: Option<String>,
+    },
+}
+
+pub async fn list(team_slug: Option<&str>) -> Result<()> {
+    let apps = client()?
+        .apps()
+        .await
+        .context("Failed to fetch apps")?
+        .into_iter()
+        .filter(|app| team_slug.is_none_or(|team_slug| app.team_slug == team_slug))
+        .collect::<Vec<_>>();
+
+    if apps.is_empty() {
+        println!("No apps found.");
+        return Ok(());
+    }
+
+    let mut table = Table::new(["SLUG", "NAME", "TEAM"]);
+    for app in apps {
+        table.add_row([app.slug, app.name, app.team_slug]);
+    }
+
+    print!("{table}");
+
+    Ok(())
+}
+
+pub async fn info(app_slug: &str) -> Result<()> {
+    let client = client()?;
+
+    let app = client
+        .app(app_slug)
+        .await
+        .with_context(|| format!("Failed to fetch app '{app_slug}'"))?;
+    let deployments = client
+        .deployments(app_slug)
+        .await
+        .with_context(|| format!("Failed to fetch deployments of app '{app_slug}'"))?;
+    let domains = client
+        .domains(app_slug)
+        .await
+        .with_context(|| format!("Failed to fetch domains of app '{app_slug}'"))?
+        .into_iter()
+        .filter(|domain| domain.status == DomainStatus::Verified)
+        .map(|domain| domain.name)
+        .collect::<Vec<_>>();
+
+    println!("Slug:        {}", app.slug);
+    println!("Name:        {}", app.name);
+    println!("Team:        {}", app.team_slug);
+    println!("URL:         {}", AppConfig::default_url(&app.slug));
+    if domains.is_empty() {
+        println!("Domains:     -");
+    } else {
+        println!("Domains:     {}", domains.join(", "));
+    }
+    match deployments.first() {
+        Some(deployment) => println!(
+            "Deployment:  {} ({}, {})",
+            deployment.id,
+            deployment.status,
+            deployment.created_at.format("%Y-%m-%d %H:%M:%S UTC")
+        ),
+        None => println!("Deployment:  -"),
+    }
+
+    Ok(())
+}
+
+pub async fn delete(app_slug: &str, confirm: Option<String>) -> Result<()> {
+    intro(format!("Delete app '{app_slug}'")).context("Failed to show delete intro")?;
+
+    warning("This deletes all deployments, logs and secrets of the app and can't be undone.")
+        .context("Failed to show warning")?;
+
+    if !confirm_slug(app_slug, confirm)? {
+        outro_cancel("Deletion cancelled").context("Failed to show cancel message")?;
+        return Ok(());
+    }
+
+    client()?
+        .delete_app(app_slug)
+        .await
+        .with_context(|| format!("Failed to delete app '{app_slug}'"))?;
+
+    outro(format!("App '{app_slug}' has been deleted"))
+        .context("Failed to show delete success message")?;
+
+    Ok(())
+}
+
+pub async fn transfer(app_slug: &str, team_slug: &str, confirm: Option<String>) -> Result<()> {
+    intro(format!("Transfer app '{app_slug}' to team '{team_slug}'"))
+        .context("Failed to show transfer intro")?;
+
+    warning("Members of the current team lose access and the team's secrets are no longer available to the app.")
+        .context("Failed to show warning")?;
+
+    if !confirm_slug(app_slug, confirm)? {
+        outro_cancel("Transfer cancelled").context("Failed to show cancel message")?;
+        return Ok(());
+    }
+
+    client()?
+        .transfer_app(app_slug, team_slug)
+        .await
+        .with_context(|| format!("Failed to transfer app '{app_slug}' to team '{team_slug}'"))?;
+
+    outro(format!(
+        "App '{app_slug}' now belongs to team '{team_slug}'"
+    ))
+    .context("Failed to show transfer success message")?;
+
+    Ok(())
+}
+
+/// Makes the user type the slug of the app to make sure the right app is affected. Scripts can
+/// pass the slug with `--confirm` instead.
+fn confirm_slug(app_slug: &str, confirm: Option<String>) -> Result<bool> {
+    if let Some(confirm) = confirm {
+        if confirm != app_slug {
+            bail!("--confirm '{confirm}' doesn't match the app slug '{app_slug}'");
+        }
+        return Ok(true);
+    }
+
+    let typed: String = input(format!("Type the app slug '{app_slug}' to confirm"))
+        .interact()
+        .context("Failed to get confirmation. Pass --confirm <SLUG> to skip it")?;
+
+    Ok(typed.trim() == app_slug)
+}
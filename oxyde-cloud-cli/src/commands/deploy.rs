@@ -1,10 +1,13 @@
 use anyhow::{Context, Result};
 use cargo_leptos::config::Opts;
-use cliclack::{intro, log::remark, outro};
+use cliclack::{ProgressBar, intro, log::remark, outro, progress_bar};
 use oxyde_cloud_common::config::CloudConfig;
+use oxyde_cloud_deploy::{DeployEvent, DeployOptions, DeployProgress, UploadEvent};
+use std::io::IsTerminal;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
 
-#[cfg(feature = "with-deploy-test")]
 pub async fn deploy(config: PathBuf) -> Result<()> {
     intro("Deploy to Oxyde Cloud").context("Failed to show deploy intro")?;
 
@@ -19,9 +22,10 @@ pub async fn deploy(config: PathBuf) -> Result<()> {
         ..Default::default()
     };
 
-    remark("Building and deploying..").context("Failed to show API key instructions")?;
+    let mut options = DeployOptions::from_env();
+    options.progress = Some(Arc::new(DeployProgressReporter::new()));
 
-    oxyde_cloud_deploy::deploy_with_config_file(&config, opts)
+    oxyde_cloud_deploy::deploy_with_config_file(&config, opts, &options)
         .await
         .context("Failed to deploy")?;
     outro(format!(
@@ -32,3 +36,90 @@ pub async fn deploy(config: PathBuf) -> Result<()> {
 
     Ok(())
 }
+
+/// Renders the upload as a progress bar in a terminal and as percentage lines otherwise, e.g.
+/// in CI logs.
+struct DeployProgressReporter {
+    bar: Mutex<Option<ProgressBar>>,
+    interactive: bool,
+    files_done: AtomicUsize,
+    last_percent: AtomicU64,
+}
+
+impl DeployProgressReporter {
+    fn new() -> Self {
+        Self {
+            bar: Mutex::new(None),
+            interactive: std::io::stdout().is_terminal(),
+            files_done: AtomicUsize::new(0),
+            last_percent: AtomicU64::new(0),
+        }
+    }
+}
+
+impl DeployProgress for DeployProgressReporter {
+    fn on_event(&self, event: DeployEvent) {
+        match event {
+            DeployEvent::Building => {
+                let _ = remark("Building..");
+            }
+            DeployEvent::Hashing { file_count } => {
+                let _ = remark(format!("Checking {file_count} files for changes.."));
+            }
+            DeployEvent::UploadStarted {
+                file_count,
+                unchanged_count,
+                total_bytes,
+            } => {
+                let _ = remark(format!(
+                    "{unchanged_count} files unchanged, uploading {file_count} files"
+                ));
+
+                if self.interactive && file_count > 0 {
+                    let bar = progress_bar(total_bytes).with_download_template();
+                    bar.start("Uploading..");
+                    *self.bar.lock().unwrap() = Some(bar);
+                }
+            }
+            DeployEvent::Upload {
+                file_count,
+                bytes_sent,
+                total_bytes,
+                event,
+                ..
+            } => {
+                let files_done = if let UploadEvent::Finished { .. } = event {
+                    self.files_done.fetch_add(1, Ordering::Relaxed) + 1
+                } else {
+                    self.files_done.load(Ordering::Relaxed)
+                };
+
+                if let Some(bar) = self.bar.lock().unwrap().as_ref() {
+                    bar.set_position(bytes_sent);
+                    bar.set_message(format!("Uploading.. ({files_done}/{file_count} files)"));
+                    return;
+                }
+
+                let percent = (bytes_sent * 100).checked_div(total_bytes).unwrap_or(100) / 10 * 10;
+                if percent > self.last_percent.fetch_max(percent, Ordering::Relaxed) {
+                    println!("Uploaded {percent}% ({files_done}/{file_count} files)");
+                }
+            }
+            DeployEvent::Finalizing => {
+                if let Some(bar) = self.bar.lock().unwrap().take() {
+                    bar.stop("Upload complete");
+                }
+                let _ = remark("Deploying..");
+            }
+            DeployEvent::Starting { .. } => {
+                let _ = remark("Waiting for the new version to start..");
+            }
+            DeployEvent::CheckingHealth { url } => {
+                let _ = remark(format!("Checking health of {url}.."));
+            }
+            DeployEvent::Deployed { url } => {
+                let _ = remark(format!("Deployed to {url}"));
+            }
+        }
+    }
+}
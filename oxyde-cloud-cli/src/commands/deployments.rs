@@ -0,0 +1,95 @@
+use crate::client::client;
+use crate::table::{Table, format_size};
+use anyhow::{Context, Result};
+use clap::Subcommand;
+use oxyde_cloud_common::net::Deployment;
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum DeploymentsCommand {
+    /// List the deployments of an app, newest first
+    List {
+        /// The name of the project to list the deployments of. Defaults to the name from the
+        /// config in the current directory
+        #[arg(short, long)]
+        name: Option<String>,
+
+        /// Sets a custom config file. Defaults to `oxyde-cloud.toml`
+        #[arg(short, long, value_name = "FILE", default_value = "oxyde-cloud.toml")]
+        config: std::path::PathBuf,
+    },
+
+    /// Show the details of a deployment
+    Show {
+        /// The id of the deployment as shown by `oxy deployments list`
+        id: String,
+    },
+}
+
+pub async fn list(app_slug: &str) -> Result<()> {
+    let client = client()?;
+
+    let deployments = client
+        .deployments(app_slug)
+        .await
+        .with_context(|| format!("Failed to fetch deployments of app '{app_slug}'"))?;
+
+    if deployments.is_empty() {
+        println!("App '{app_slug}' hasn't been deployed yet.");
+        return Ok(());
+    }
+
+    let mut table = Table::new(["ID", "STATUS", "CREATED", "CREATOR", "COMMIT", "SIZE"]);
+    for deployment in &deployments {
+        table.add_row([
+            deployment.id.clone(),
+            deployment.status.to_string(),
+            format_time(deployment),
+            deployment.creator.clone(),
+            short_commit(deployment).to_string(),
+            format_size(deployment.size),
+        ]);
+    }
+
+    print!("{table}");
+
+    Ok(())
+}
+
+pub async fn show(id: &str) -> Result<()> {
+    let client = client()?;
+
+    let deployment = client
+        .deployment(id)
+        .await
+        .with_context(|| format!("Failed to fetch deployment '{id}'"))?;
+
+    println!("ID:       {}", deployment.id);
+    println!("App:      {}", deployment.app_slug);
+    println!("Status:   {}", deployment.status);
+    println!("Created:  {}", format_time(&deployment));
+    println!("Creator:  {}", deployment.creator);
+    println!(
+        "Commit:   {}",
+        deployment.commit_sha.as_deref().unwrap_or("-")
+    );
+    println!("Size:     {}", format_size(deployment.size));
+
+    Ok(())
+}
+
+fn format_time(deployment: &Deployment) -> String {
+    deployment
+        .created_at
+        .format("%Y-%m-%d %H:%M:%S UTC")
+        .to_string()
+}
+
+fn short_commit(deployment: &Deployment) -> &str {
+    match &deployment.commit_sha {
+        Some(sha) => sha
+            .char_indices()
+            .nth(7)
+            .map_or(sha, |(end, _)| &sha[..end]),
+        None => "-",
+    }
+}
@@ -0,0 +1,176 @@
+use crate::client::client;
+use crate::table::Table;
+use anyhow::{Context, Result};
+use clap::{Args, Subcommand};
+use cliclack::confirm;
+use oxyde_cloud_common::config::DomainsConfig;
+use oxyde_cloud_common::net::{Domain, DomainStatus};
+use std::path::PathBuf;
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum DomainsCommand {
+    /// Add a custom domain and show the DNS records it needs
+    Add {
+        /// The domain like `www.example.com`
+        domain: String,
+
+        #[command(flatten)]
+        app: AppArgs,
+    },
+
+    /// Check the DNS records of a custom domain
+    Verify {
+        /// The domain that was added with `oxy domains add`
+        domain: String,
+
+        #[command(flatten)]
+        app: AppArgs,
+    },
+
+    /// List the custom domains with their verification and certificate status
+    List {
+        #[command(flatten)]
+        app: AppArgs,
+    },
+
+    /// Remove a custom domain
+    Rm {
+        /// The domain to remove like `www.example.com`
+        domain: String,
+
+        /// Don't ask for confirmation
+        #[arg(short, long)]
+        yes: bool,
+
+        #[command(flatten)]
+        app: AppArgs,
+    },
+}
+
+/// Selects the app whose domains are managed.
+#[derive(Args, Debug, Clone)]
+pub struct AppArgs {
+    /// The name of the app. Defaults to the name from the config in the current directory
+    #[arg(short, long)]
+    pub name: Option<String>,
+
+    /// Sets a custom config file. Defaults to `oxyde-cloud.toml`
+    #[arg(short, long, value_name = "FILE", default_value = "oxyde-cloud.toml")]
+    pub config: PathBuf,
+}
+
+pub async fn add(app_slug: &str, domain: &str) -> Result<()> {
+    if !DomainsConfig::is_valid_domain(domain) {
+        anyhow::bail!(
+            "Invalid domain '{domain}'. Domains have to be lower case like 'www.example.com'"
+        );
+    }
+
+    let domain = client()?
+        .add_domain(app_slug, domain)
+        .await
+        .with_context(|| format!("Failed to add domain {domain} to app '{app_slug}'"))?;
+
+    println!(
+        "Added {} to app '{app_slug}'. Set up these DNS records at your DNS provider:",
+        domain.name
+    );
+    println!();
+    print_records(&domain);
+    println!();
+    println!(
+        "Then run `oxy domains verify {}`. Add the domain to [domains] in oxyde-cloud.toml so deploys use it once it's verified.",
+        domain.name
+    );
+
+    Ok(())
+}
+
+pub async fn verify(app_slug: &str, domain: &str) -> Result<()> {
+    let domain = client()?
+        .verify_domain(app_slug, domain)
+        .await
+        .with_context(|| format!("Failed to verify domain {domain}"))?;
+
+    match domain.status {
+        DomainStatus::Verified => println!(
+            "{} is verified. Certificate: {}",
+            domain.name, domain.certificate
+        ),
+        DomainStatus::Pending => {
+            println!(
+                "The DNS records of {} weren't found yet. DNS changes can take a while to propagate. Expected records:",
+                domain.name
+            );
+            println!();
+            print_records(&domain);
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn list(app_slug: &str) -> Result<()> {
+    let domains = client()?
+        .domains(app_slug)
+        .await
+        .with_context(|| format!("Failed to fetch domains of app '{app_slug}'"))?;
+
+    if domains.is_empty() {
+        println!("App '{app_slug}' has no custom domains.");
+        return Ok(());
+    }
+
+    let mut table = Table::new(["DOMAIN", "STATUS", "CERTIFICATE", "VERIFIED"]);
+    for domain in domains {
+        table.add_row([
+            domain.name,
+            domain.status.to_string(),
+            domain.certificate.to_string(),
+            domain
+                .verified_at
+                .map_or_else(|| "-".to_string(), |at| at.format("%Y-%m-%d").to_string()),
+        ]);
+    }
+
+    print!("{table}");
+
+    Ok(())
+}
+
+pub async fn rm(app_slug: &str, domain: &str, yes: bool) -> Result<()> {
+    if !yes {
+        let confirmed = confirm(format!(
+            "Remove {domain} from app '{app_slug}'? It stops serving the app right away."
+        ))
+        .interact()
+        .context("Failed to get confirmation. Pass --yes to skip it")?;
+
+        if !confirmed {
+            println!("Nothing was changed.");
+            return Ok(());
+        }
+    }
+
+    client()?
+        .remove_domain(app_slug, domain)
+        .await
+        .with_context(|| format!("Failed to remove domain {domain}"))?;
+
+    println!("Removed {domain} from app '{app_slug}'.");
+
+    Ok(())
+}
+
+fn print_records(domain: &Domain) {
+    let mut table = Table::new(["TYPE", "NAME", "VALUE"]);
+    for record in &domain.records {
+        table.add_row([
+            record.kind.to_string(),
+            record.name.clone(),
+            record.value.clone(),
+        ]);
+    }
+
+    print!("{table}");
+}
@@ -1,30 +1,20 @@
-use crate::api_key::api_key;
+use super::prompt::Prompt;
 use anyhow::{Context, Result};
-use cliclack::{input, spinner};
+use cliclack::spinner;
 use oxyde_cloud_client::Client;
-use oxyde_cloud_common::config::AppConfig;
-
-pub(super) async fn input_app_slug(team_slug: &str) -> Result<String> {
-    let api_key = api_key().context("Failed to get API key")?;
 
+/// Asks for app slugs until one is available and creates the app in the team.
+pub async fn input_app_slug(
+    client: &Client,
+    team_slug: &str,
+    prompt: &mut impl Prompt,
+) -> Result<String> {
     loop {
-        let app_slug: String = input("Enter app slug [a-z0-9_-]:")
-            .placeholder("your-app-name-42")
-            .validate_interactively(|input: &String| {
-                if AppConfig::is_valid_slug(input) {
-                    Ok(())
-                } else {
-                    Err(AppConfig::slug_requirements())
-                }
-            })
-            .interact()
-            .context("Failed to get app slug input")?;
+        let app_slug = prompt.app_slug()?;
 
         let spinner = spinner();
         spinner.start(format!(r#"Checking availability for slug "{app_slug}"..."#));
 
-        let client = Client::new(api_key.clone());
-
         if client
             .new_app(&app_slug, team_slug, &app_slug)
             .await
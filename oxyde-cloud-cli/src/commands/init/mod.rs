@@ -4,12 +4,15 @@ use cliclack::{intro, outro};
 use std::path::PathBuf;
 use tera::Context;
 
-mod app_slug;
-mod team;
+pub mod app_slug;
+pub mod prompt;
+pub mod team;
 
+use crate::client::client;
 use crate::commands::TEMPLATES;
 use crate::commands::deploy_config::init_deploy_config;
 use crate::commands::init::app_slug::input_app_slug;
+use crate::commands::init::prompt::TerminalPrompt;
 use crate::commands::init::team::input_team_slug;
 
 pub async fn init(
@@ -25,7 +28,9 @@ pub async fn init(
                 .context("Failed to show team slug remark")?;
             team_slug
         }
-        None => input_team_slug().await.context("Failed to get team slug")?,
+        None => input_team_slug(&client()?, &mut TerminalPrompt)
+            .await
+            .context("Failed to get team slug")?,
     };
 
     let app_slug = match app_slug {
@@ -34,7 +39,7 @@ pub async fn init(
                 .context("Failed to show app slug remark")?;
             slug
         }
-        None => input_app_slug(&team_slug)
+        None => input_app_slug(&client()?, &team_slug, &mut TerminalPrompt)
             .await
             .context("Failed to get app slug")?,
     };
@@ -0,0 +1,72 @@
+use anyhow::{Context, Result};
+use cliclack::{input, select};
+use oxyde_cloud_common::config::AppConfig;
+use oxyde_cloud_common::net::Team;
+
+/// Answers the questions `oxy init` asks. [`TerminalPrompt`] asks the user, tests script the
+/// answers.
+pub trait Prompt {
+    /// The slug of a new team.
+    fn new_team_slug(&mut self) -> Result<String>;
+
+    /// The display name of a new team. An empty answer picks `default`.
+    fn team_name(&mut self, default: &str) -> Result<String>;
+
+    /// The slug of the team the app belongs to, out of `teams`.
+    fn select_team(&mut self, teams: &[Team]) -> Result<String>;
+
+    /// The slug of the new app.
+    fn app_slug(&mut self) -> Result<String>;
+}
+
+/// Asks the user in the terminal.
+pub struct TerminalPrompt;
+
+impl Prompt for TerminalPrompt {
+    fn new_team_slug(&mut self) -> Result<String> {
+        input("Creating new team. Enter unique team slug [a-z0-9_-]:")
+            .placeholder("your-team-name-42")
+            .validate_interactively(|input: &String| {
+                if AppConfig::is_valid_slug(input) {
+                    Ok(())
+                } else {
+                    Err(format!("Team slug must be at least {} characters long, lower case alphanumeric and can contain underscores or dashes.", AppConfig::MIN_SLUG_LENGTH))
+                }
+            })
+            .interact()
+            .context("Failed to get team slug input")
+    }
+
+    fn team_name(&mut self, default: &str) -> Result<String> {
+        input("Enter team display name:")
+            .default_input(default)
+            .interact()
+            .context("Failed to get team name input")
+    }
+
+    fn select_team(&mut self, teams: &[Team]) -> Result<String> {
+        select("Select the team this app should belong to:")
+            .items(
+                &teams
+                    .iter()
+                    .map(|t| (t.slug.clone(), t.name.clone(), ""))
+                    .collect::<Vec<_>>(),
+            )
+            .interact()
+            .context("Failed to get team selection")
+    }
+
+    fn app_slug(&mut self) -> Result<String> {
+        input("Enter app slug [a-z0-9_-]:")
+            .placeholder("your-app-name-42")
+            .validate_interactively(|input: &String| {
+                if AppConfig::is_valid_slug(input) {
+                    Ok(())
+                } else {
+                    Err(AppConfig::slug_requirements())
+                }
+            })
+            .interact()
+            .context("Failed to get app slug input")
+    }
+}
@@ -1,18 +1,14 @@
-use crate::api_key::api_key;
+use super::prompt::Prompt;
 use anyhow::{Context, Result};
-use cliclack::{input, select, spinner};
+use cliclack::spinner;
 use heck::ToTitleCase;
 use oxyde_cloud_client::Client;
-use oxyde_cloud_common::config::AppConfig;
-
-pub(super) async fn input_team_slug() -> Result<String> {
-    let api_key = api_key().context("Failed to get API key")?;
 
+/// Picks the team of the new app. Users without a team create one, users with several choose.
+pub async fn input_team_slug(client: &Client, prompt: &mut impl Prompt) -> Result<String> {
     let spinner = spinner();
     spinner.start("Loading teams...");
 
-    let client = Client::new(api_key.clone());
-
     let teams = client
         .teams()
         .await
@@ -20,7 +16,7 @@ pub(super) async fn input_team_slug() -> Result<String> {
 
     if teams.is_empty() {
         spinner.stop("No teams found.");
-        return input_new_team(api_key).await;
+        return input_new_team(client, prompt).await;
     }
 
     if teams.len() == 1 {
@@ -30,40 +26,18 @@ pub(super) async fn input_team_slug() -> Result<String> {
 
     spinner.clear();
 
-    let team_slug = select("Select the team this app should belong to:")
-        .items(
-            &teams
-                .into_iter()
-                .map(|t| (t.slug, t.name, ""))
-                .collect::<Vec<_>>(),
-        )
-        .interact()
-        .context("Failed to get team selection")?;
-
-    Ok(team_slug)
+    prompt.select_team(&teams)
 }
 
-async fn input_new_team(api_key: String) -> Result<String> {
+async fn input_new_team(client: &Client, prompt: &mut impl Prompt) -> Result<String> {
     loop {
-        let team_slug: String = input("Creating new team. Enter unique team slug [a-z0-9_-]:")
-            .placeholder("your-team-name-42")
-            .validate_interactively(|input: &String| {
-                if AppConfig::is_valid_slug(input) {
-                    Ok(())
-                } else {
-                    Err(format!("Team slug must be at least {} characters long, lower case alphanumeric and can contain underscores or dashes.", AppConfig::MIN_SLUG_LENGTH))
-                }
-            })
-            .interact()
-            .context("Failed to get team slug input")?;
+        let team_slug = prompt.new_team_slug()?;
 
         let spinner = spinner();
         spinner.start(format!(
             r#"Checking availability for team slug "{team_slug}"..."#
         ));
 
-        let client = Client::new(api_key.clone());
-
         if client
             .new_team(&team_slug)
             .await
@@ -71,7 +45,7 @@ async fn input_new_team(api_key: String) -> Result<String> {
         {
             spinner.stop("Slug confirmed");
 
-            input_new_team_name(&team_slug, api_key)
+            input_new_team_name(&team_slug, client, prompt)
                 .await
                 .context("Failed to set team name")?;
 
@@ -84,13 +58,14 @@ async fn input_new_team(api_key: String) -> Result<String> {
     }
 }
 
-async fn input_new_team_name(team_slug: &str, api_key: String) -> Result<()> {
+async fn input_new_team_name(
+    team_slug: &str,
+    client: &Client,
+    prompt: &mut impl Prompt,
+) -> Result<()> {
     let default_name = team_slug.to_title_case();
 
-    let mut name: String = input("Enter team display name:")
-        .default_input(&default_name)
-        .interact()
-        .context("Failed to get team name input")?;
+    let mut name = prompt.team_name(&default_name)?;
 
     if name.is_empty() {
         name = default_name;
@@ -99,8 +74,6 @@ async fn input_new_team_name(team_slug: &str, api_key: String) -> Result<()> {
     let spinner = spinner();
     spinner.start(format!(r#"Saving team name "{name}"..."#));
 
-    let client = Client::new(api_key);
-
     client
         .set_team_name(team_slug, &name)
         .await
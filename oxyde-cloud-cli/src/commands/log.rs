@@ -1,17 +1,153 @@
-use crate::api_key::api_key;
+use crate::client::client;
+use crate::time::{Direction, parse_relative_time};
 use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+use futures_util::StreamExt;
 use oxyde_cloud_client::Client;
+use oxyde_cloud_common::net::{
+    LogEntry, LogFilter, LogLevel, LogRequest, LogStreamRequest, OutputStream,
+};
 
-pub async fn log(name: &str) -> Result<()> {
-    let api_key = api_key().context("Failed to get API key")?;
-    let client = Client::new(api_key);
+#[derive(clap::Args, Debug, Clone)]
+pub struct LogArgs {
+    /// Keep printing new entries as they arrive until Ctrl-C is pressed
+    #[arg(short, long, conflicts_with = "until")]
+    pub follow: bool,
 
-    let logs = client
-        .log(name)
+    /// Only show entries written since this time. Either a timestamp like
+    /// `2025-01-31T12:00:00Z` or a duration ago like `30s`, `15m`, `2h` or `1d`
+    #[arg(long, value_name = "TIME", value_parser = |value: &str| parse_relative_time(value, Direction::Past))]
+    pub since: Option<DateTime<Utc>>,
+
+    /// Only show entries written before this time. Same format as `--since`
+    #[arg(long, value_name = "TIME", value_parser = |value: &str| parse_relative_time(value, Direction::Past))]
+    pub until: Option<DateTime<Utc>>,
+
+    /// Only show the last N matching entries
+    #[arg(long, value_name = "N")]
+    pub tail: Option<usize>,
+
+    /// Only show entries of this level or more severe [trace, debug, info, warn, error]
+    #[arg(short, long)]
+    pub level: Option<LogLevel>,
+
+    /// Only show entries of the app instance with this id
+    #[arg(long, value_name = "ID")]
+    pub instance: Option<String>,
+
+    /// Only show entries whose message contains this text
+    #[arg(short, long, value_name = "TEXT", conflicts_with = "regex")]
+    pub grep: Option<String>,
+
+    /// Only show entries whose message matches this regular expression
+    #[arg(long, value_name = "PATTERN")]
+    pub regex: Option<String>,
+
+    /// How to print the entries
+    #[arg(long, value_enum, default_value_t)]
+    pub format: LogFormat,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, Default)]
+pub enum LogFormat {
+    /// Only the messages, one per line
+    #[default]
+    Text,
+    /// Timestamp, level, instance and stream before every message
+    Full,
+    /// One JSON object per entry and line
+    Json,
+}
+
+pub async fn log(name: &str, args: LogArgs) -> Result<()> {
+    let client = client()?;
+    let filter = args
+        .grep
+        .map(LogFilter::Text)
+        .or(args.regex.map(LogFilter::Regex));
+
+    if args.follow {
+        let request = LogStreamRequest {
+            since: args.since,
+            tail: args.tail,
+            level: args.level,
+            instance: args.instance,
+            filter,
+            ..LogStreamRequest::new(name)
+        };
+
+        return follow_log(&client, &request, args.format).await;
+    }
+
+    let request = LogRequest {
+        since: args.since,
+        until: args.until,
+        tail: args.tail,
+        level: args.level,
+        instance: args.instance,
+        filter,
+        ..LogRequest::new(name)
+    };
+
+    let entries = client
+        .log(&request)
         .await
         .with_context(|| format!("Failed to fetch logs for app '{name}'"))?;
 
-    println!("{logs}");
+    for entry in &entries {
+        print_entry(entry, args.format)?;
+    }
 
     Ok(())
 }
+
+fn print_entry(entry: &LogEntry, format: LogFormat) -> Result<()> {
+    println!("{}", format_entry(entry, format)?);
+
+    Ok(())
+}
+
+/// Renders an entry as one line. Fetched and followed logs are both printed this way.
+pub fn format_entry(entry: &LogEntry, format: LogFormat) -> Result<String> {
+    let line = match format {
+        LogFormat::Text => entry.message.clone(),
+        LogFormat::Full => {
+            let stream = match entry.stream {
+                OutputStream::Stdout => "stdout",
+                OutputStream::Stderr => "stderr",
+            };
+
+            format!(
+                "{} {:>5} {} {stream}: {}",
+                entry.timestamp.format("%Y-%m-%dT%H:%M:%S%.3fZ"),
+                entry.level.as_str().to_uppercase(),
+                entry.instance,
+                entry.message
+            )
+        }
+        LogFormat::Json => serde_json::to_string(entry).context("Failed to serialize log entry")?,
+    };
+
+    Ok(line)
+}
+
+/// Prints the entries of the log as they're written until Ctrl-C is pressed.
+async fn follow_log(client: &Client, request: &LogStreamRequest, format: LogFormat) -> Result<()> {
+    let mut entries = std::pin::pin!(client.log_stream(request));
+
+    loop {
+        tokio::select! {
+            entry = entries.next() => {
+                let Some(entry) = entry else {
+                    return Ok(());
+                };
+                let entry = entry
+                    .with_context(|| format!("Failed to follow logs for app '{}'", request.name))?;
+
+                print_entry(&entry, format)?;
+            }
+            _ = tokio::signal::ctrl_c() => return Ok(()),
+        }
+    }
+}
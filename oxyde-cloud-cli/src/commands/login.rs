@@ -1,10 +1,10 @@
 use crate::api_key::api_key_entry;
+use crate::client::client_with_api_key;
 use crate::commands::logout::logout;
 use anyhow::{Context, Result};
 use axum::{Router, extract::Query, response::Html, routing::get};
 use cliclack::log::remark;
 use cliclack::{intro, outro, outro_cancel, spinner};
-use oxyde_cloud_client::Client;
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::net::TcpListener;
@@ -16,10 +16,10 @@ async fn handle_callback(
 ) -> Html<String> {
     if let Some(api_key) = params.get("api_key") {
         // Send the API key back to the main thread
-        if let Ok(mut sender_opt) = tx.try_lock() {
-            if let Some(sender) = sender_opt.take() {
-                let _ = sender.send(api_key.clone());
-            }
+        if let Ok(mut sender_opt) = tx.try_lock()
+            && let Some(sender) = sender_opt.take()
+        {
+            let _ = sender.send(api_key.clone());
         }
 
         r#"
@@ -143,7 +143,7 @@ pub async fn login() -> Result<()> {
     let login_spinner = spinner();
     login_spinner.start("Logging in...");
 
-    match Client::new(api_key).login().await {
+    match client_with_api_key(api_key)?.login().await {
         Ok(login_result) => {
             login_spinner.stop("Done!");
             outro(format!(
@@ -1,12 +1,20 @@
 use lazy_static::lazy_static;
 use tera::Tera;
 
+pub mod apps;
+#[cfg(feature = "with-deploy-test")]
 pub mod deploy;
 pub mod deploy_config;
+pub mod deployments;
+pub mod domains;
 pub mod init;
 pub mod log;
 pub mod login;
 pub mod logout;
+pub mod rollback;
+pub mod secrets;
+pub mod teams;
+pub mod tokens;
 
 lazy_static! {
     pub static ref TEMPLATES: Tera = {
@@ -0,0 +1,111 @@
+use crate::client::client;
+use anyhow::{Context, Result, bail};
+use cliclack::{confirm, intro, outro, outro_cancel, spinner};
+use oxyde_cloud_client::ClientError;
+use oxyde_cloud_common::net::{Deployment, DeploymentStatus};
+use std::time::Duration;
+
+/// How long to wait for the rolled back version to go live.
+const ROLLBACK_TIMEOUT: Duration = Duration::from_secs(300);
+
+pub async fn rollback(app_slug: &str, to: Option<String>, yes: bool) -> Result<()> {
+    intro(format!("Roll back app '{app_slug}'")).context("Failed to show rollback intro")?;
+
+    let client = client()?;
+
+    let deployments = client
+        .deployments(app_slug)
+        .await
+        .with_context(|| format!("Failed to fetch deployments of app '{app_slug}'"))?;
+
+    let target = match to {
+        Some(id) => {
+            let Some(target) = deployments.iter().find(|d| d.id == id) else {
+                bail!("App '{app_slug}' has no deployment '{id}'");
+            };
+            if target.status != DeploymentStatus::Success {
+                bail!(
+                    "Deployment '{id}' has status {}. Only successful deployments can be rolled back to",
+                    target.status
+                );
+            }
+            target
+        }
+        None => previous_successful(&deployments).with_context(|| {
+            format!("App '{app_slug}' has no successful deployment before the current one")
+        })?,
+    };
+
+    if !yes {
+        let confirmed = confirm(format!(
+            "Roll back app '{app_slug}' to deployment {} from {}?",
+            target.id,
+            target.created_at.format("%Y-%m-%d %H:%M:%S UTC"),
+        ))
+        .interact()
+        .context("Failed to get confirmation. Pass --yes to skip it")?;
+
+        if !confirmed {
+            outro_cancel("Rollback cancelled").context("Failed to show cancel message")?;
+            return Ok(());
+        }
+    }
+
+    let spinner = spinner();
+    spinner.start(format!("Rolling back to deployment {}...", target.id));
+
+    let deployment_id = client
+        .rollback(app_slug, &target.id)
+        .await
+        .with_context(|| format!("Failed to roll back to deployment '{}'", target.id))?;
+
+    match client
+        .wait_for_deployment(&deployment_id, ROLLBACK_TIMEOUT)
+        .await
+    {
+        Ok(_) => spinner.stop(format!("Deployment {deployment_id} is live")),
+        Err(ClientError::DeploymentFailed { startup_log, .. }) => {
+            spinner.error(format!("Deployment {deployment_id} failed to start"));
+
+            let mut message = format!("Rollback deployment {deployment_id} failed to start");
+            for entry in startup_log {
+                message.push_str("\n  ");
+                message.push_str(&entry.message);
+            }
+            bail!(message);
+        }
+        Err(err) => {
+            spinner.error("Failed to confirm the rollback");
+            return Err(err).context("Failed to confirm that the rollback is live");
+        }
+    }
+
+    outro(format!(
+        "App '{app_slug}' has been rolled back to deployment {}",
+        target.id
+    ))
+    .context("Failed to show rollback success message")?;
+
+    Ok(())
+}
+
+/// The last successful release before the current one. Deployments are ordered newest first
+/// and the current one is the newest successful deployment.
+///
+/// After a rollback the current deployment is a copy of an older release. Everything newer than
+/// that release is skipped, so rolling back again goes further back instead of returning to the
+/// release that was just rolled away from.
+pub fn previous_successful(deployments: &[Deployment]) -> Option<&Deployment> {
+    let successful = deployments
+        .iter()
+        .filter(|d| d.status == DeploymentStatus::Success)
+        .collect::<Vec<_>>();
+
+    let release = successful.first()?.release_id();
+    let older = match successful.iter().position(|d| d.id == release) {
+        Some(index) => &successful[index + 1..],
+        None => &successful[1..],
+    };
+
+    older.iter().copied().find(|d| d.release_id() != release)
+}
@@ -0,0 +1,140 @@
+use crate::client::client;
+use crate::table::Table;
+use anyhow::{Context, Result, bail};
+use clap::{Args, Subcommand};
+use cliclack::password;
+use oxyde_cloud_common::net::SecretScope;
+use oxyde_cloud_common::secrets::is_valid_secret_name;
+use std::io::{IsTerminal, Read};
+use std::path::PathBuf;
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum SecretsCommand {
+    /// Create a secret or replace its value. The value is read from a hidden prompt or, if
+    /// something is piped in, from stdin
+    Set {
+        /// The name to reference the secret by as `${{ secrets.NAME }}`
+        name: String,
+
+        #[command(flatten)]
+        scope: SecretScopeArgs,
+    },
+
+    /// List the names of the secrets. Values are never shown
+    List {
+        #[command(flatten)]
+        scope: SecretScopeArgs,
+    },
+
+    /// Delete a secret
+    Rm {
+        /// The name of the secret to delete
+        name: String,
+
+        #[command(flatten)]
+        scope: SecretScopeArgs,
+    },
+}
+
+/// Selects the app or team the secrets belong to.
+#[derive(Args, Debug, Clone)]
+pub struct SecretScopeArgs {
+    /// The app the secrets belong to. Defaults to the name from the config in the current
+    /// directory
+    #[arg(short, long)]
+    pub app: Option<String>,
+
+    /// Manage the secrets shared by all apps of this team instead of those of an app
+    #[arg(short, long, conflicts_with = "app")]
+    pub team: Option<String>,
+
+    /// Sets a custom config file. Defaults to `oxyde-cloud.toml`
+    #[arg(short, long, value_name = "FILE", default_value = "oxyde-cloud.toml")]
+    pub config: PathBuf,
+}
+
+pub async fn set(scope: &SecretScope, name: &str) -> Result<()> {
+    if !is_valid_secret_name(name) {
+        bail!(
+            "Invalid secret name '{name}'. Names can only contain letters, digits and underscores and must not start with a digit"
+        );
+    }
+
+    let value = read_value(name)?;
+    if value.is_empty() {
+        bail!("The value of a secret can't be empty");
+    }
+
+    client()?
+        .set_secret(scope, name, &value)
+        .await
+        .with_context(|| format!("Failed to set secret '{name}' of {scope}"))?;
+
+    println!("Secret '{name}' of {scope} has been set.");
+
+    Ok(())
+}
+
+pub async fn list(scope: &SecretScope) -> Result<()> {
+    let secrets = client()?
+        .list_secrets(scope)
+        .await
+        .with_context(|| format!("Failed to fetch secrets of {scope}"))?;
+
+    if secrets.is_empty() {
+        println!("There are no secrets for {scope} yet.");
+        return Ok(());
+    }
+
+    let mut table = Table::new(["NAME", "UPDATED"]);
+    for secret in &secrets {
+        table.add_row([
+            secret.name.clone(),
+            secret
+                .updated_at
+                .format("%Y-%m-%d %H:%M:%S UTC")
+                .to_string(),
+        ]);
+    }
+
+    print!("{table}");
+
+    Ok(())
+}
+
+pub async fn rm(scope: &SecretScope, name: &str) -> Result<()> {
+    client()?
+        .delete_secret(scope, name)
+        .await
+        .with_context(|| format!("Failed to delete secret '{name}' of {scope}"))?;
+
+    println!("Secret '{name}' of {scope} has been deleted.");
+
+    Ok(())
+}
+
+/// Prompts for the value without echoing it, or reads it from stdin when that isn't a terminal
+/// so values can be piped in from files or other tools.
+fn read_value(name: &str) -> Result<String> {
+    let mut stdin = std::io::stdin();
+
+    if stdin.is_terminal() {
+        return password(format!("Value of secret '{name}'"))
+            .mask('▪')
+            .interact()
+            .context("Failed to read secret value");
+    }
+
+    let mut value = String::new();
+    stdin
+        .read_to_string(&mut value)
+        .context("Failed to read secret value from stdin")?;
+
+    // Drop the line break that `echo` and most files end with.
+    let trimmed = value
+        .strip_suffix('\n')
+        .map(|v| v.strip_suffix('\r').unwrap_or(v))
+        .unwrap_or(&value);
+
+    Ok(trimmed.to_string())
+}
@@ -0,0 +1,164 @@
+use crate::client::client;
+use crate::table::Table;
+use anyhow::{Context, Result};
+use clap::{Args, Subcommand};
+use cliclack::confirm;
+use oxyde_cloud_common::net::TeamRole;
+use std::path::PathBuf;
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum TeamsCommand {
+    /// List the members and pending invitations of a team
+    Members {
+        #[command(flatten)]
+        team: TeamArgs,
+    },
+
+    /// Invite someone to join a team by email
+    Invite {
+        /// The email address to send the invitation to
+        email: String,
+
+        /// The role they get once they accept [owner, admin, deployer, viewer]
+        #[arg(short, long, default_value = "deployer")]
+        role: TeamRole,
+
+        #[command(flatten)]
+        team: TeamArgs,
+    },
+
+    /// Remove a member from a team
+    Remove {
+        /// The username of the member to remove
+        username: String,
+
+        /// Don't ask for confirmation
+        #[arg(short, long)]
+        yes: bool,
+
+        #[command(flatten)]
+        team: TeamArgs,
+    },
+
+    /// Change the role of a member
+    Role {
+        /// The username of the member
+        username: String,
+
+        /// The new role [owner, admin, deployer, viewer]
+        role: TeamRole,
+
+        #[command(flatten)]
+        team: TeamArgs,
+    },
+}
+
+/// Selects the team to manage.
+#[derive(Args, Debug, Clone)]
+pub struct TeamArgs {
+    /// The slug of the team. Defaults to the team of the app from the config in the current
+    /// directory
+    #[arg(short, long)]
+    pub team: Option<String>,
+
+    /// Sets a custom config file. Defaults to `oxyde-cloud.toml`
+    #[arg(short, long, value_name = "FILE", default_value = "oxyde-cloud.toml")]
+    pub config: PathBuf,
+}
+
+/// The slug of the team that owns an app.
+pub async fn team_of_app(app_slug: &str) -> Result<String> {
+    let app = client()?
+        .app(app_slug)
+        .await
+        .with_context(|| format!("Failed to fetch app '{app_slug}'"))?;
+
+    Ok(app.team_slug)
+}
+
+pub async fn members(team_slug: &str) -> Result<()> {
+    let client = client()?;
+
+    let members = client
+        .team_members(team_slug)
+        .await
+        .with_context(|| format!("Failed to fetch members of team '{team_slug}'"))?;
+    let invitations = client
+        .team_invitations(team_slug)
+        .await
+        .with_context(|| format!("Failed to fetch invitations of team '{team_slug}'"))?;
+
+    let mut table = Table::new(["USERNAME", "ROLE", "JOINED"]);
+    for member in members {
+        table.add_row([
+            member.username,
+            member.role.to_string(),
+            member.joined_at.format("%Y-%m-%d").to_string(),
+        ]);
+    }
+    print!("{table}");
+
+    if !invitations.is_empty() {
+        println!();
+        println!("Pending invitations:");
+
+        let mut table = Table::new(["EMAIL", "ROLE", "INVITED BY", "SENT"]);
+        for invitation in invitations {
+            table.add_row([
+                invitation.email,
+                invitation.role.to_string(),
+                invitation.invited_by,
+                invitation.created_at.format("%Y-%m-%d").to_string(),
+            ]);
+        }
+        print!("{table}");
+    }
+
+    Ok(())
+}
+
+pub async fn invite(team_slug: &str, email: &str, role: TeamRole) -> Result<()> {
+    client()?
+        .invite_member(team_slug, email, role)
+        .await
+        .with_context(|| format!("Failed to invite '{email}' to team '{team_slug}'"))?;
+
+    println!("Invited {email} to team '{team_slug}' as {role}.");
+
+    Ok(())
+}
+
+pub async fn remove(team_slug: &str, username: &str, yes: bool) -> Result<()> {
+    if !yes {
+        let confirmed = confirm(format!("Remove {username} from team '{team_slug}'?"))
+            .interact()
+            .context("Failed to get confirmation. Pass --yes to skip it")?;
+
+        if !confirmed {
+            println!("Nothing was changed.");
+            return Ok(());
+        }
+    }
+
+    client()?
+        .remove_member(team_slug, username)
+        .await
+        .with_context(|| format!("Failed to remove {username} from team '{team_slug}'"))?;
+
+    println!("Removed {username} from team '{team_slug}'.");
+
+    Ok(())
+}
+
+pub async fn role(team_slug: &str, username: &str, role: TeamRole) -> Result<()> {
+    client()?
+        .set_member_role(team_slug, username, role)
+        .await
+        .with_context(|| {
+            format!("Failed to change the role of {username} in team '{team_slug}'")
+        })?;
+
+    println!("{username} is now {role} of team '{team_slug}'.");
+
+    Ok(())
+}
@@ -0,0 +1,142 @@
+use crate::client::client;
+use crate::table::Table;
+use crate::time::{Direction, parse_relative_time};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use clap::Subcommand;
+use cliclack::{intro, log::remark, note, outro};
+use oxyde_cloud_common::net::{NewTokenRequest, TokenRestriction, TokenScope};
+use std::path::PathBuf;
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum TokensCommand {
+    /// Create an API token, e.g. to deploy from CI. The token is shown only once
+    Create {
+        /// Describes what the token is used for, like the repository it's stored in
+        name: String,
+
+        /// What the token is allowed to do [read, deploy, secrets, admin]. Can be given
+        /// multiple times
+        #[arg(short, long = "scope", value_name = "SCOPE", default_value = "deploy")]
+        scopes: Vec<TokenScope>,
+
+        /// Only allow access to this app. Defaults to the app from the config in the current
+        /// directory unless --team or --unrestricted is given
+        #[arg(short, long, conflicts_with_all = ["team", "unrestricted"])]
+        app: Option<String>,
+
+        /// Only allow access to the apps of this team
+        #[arg(short, long, conflicts_with = "unrestricted")]
+        team: Option<String>,
+
+        /// Allow access to everything you have access to
+        #[arg(long)]
+        unrestricted: bool,
+
+        /// When the token stops working, either a timestamp like `2026-01-31T00:00:00Z` or a
+        /// duration from now like `90d`. Defaults to never
+        #[arg(short, long, value_parser = |value: &str| parse_relative_time(value, Direction::Future))]
+        expires: Option<DateTime<Utc>>,
+
+        /// Sets a custom config file. Defaults to `oxyde-cloud.toml`
+        #[arg(short, long, value_name = "FILE", default_value = "oxyde-cloud.toml")]
+        config: PathBuf,
+    },
+
+    /// List your API tokens
+    List,
+
+    /// Revoke an API token
+    Revoke {
+        /// The id of the token as shown by `oxy tokens list`
+        id: String,
+    },
+}
+
+pub async fn create(
+    name: &str,
+    scopes: Vec<TokenScope>,
+    restriction: Option<TokenRestriction>,
+    expires_at: Option<DateTime<Utc>>,
+) -> Result<()> {
+    intro(format!("Create API token '{name}'")).context("Failed to show token intro")?;
+
+    let request = NewTokenRequest {
+        restriction,
+        expires_at,
+        ..NewTokenRequest::new(name, scopes)
+    };
+
+    let created = client()?
+        .create_token(&request)
+        .await
+        .context("Failed to create API token")?;
+
+    note("API token", &created.secret).context("Failed to show API token")?;
+    remark(
+        "Copy it now, it won't be shown again. To deploy from GitHub Actions store it as the repository secret OXYDE_CLOUD_TOKEN.",
+    )
+    .context("Failed to show token hint")?;
+
+    outro(format!("Created token {}", created.token.id))
+        .context("Failed to show token success message")?;
+
+    Ok(())
+}
+
+pub async fn list() -> Result<()> {
+    let tokens = client()?
+        .list_tokens()
+        .await
+        .context("Failed to fetch API tokens")?;
+
+    if tokens.is_empty() {
+        println!("You don't have any API tokens yet.");
+        return Ok(());
+    }
+
+    let mut table = Table::new([
+        "ID",
+        "NAME",
+        "SCOPES",
+        "RESTRICTED TO",
+        "EXPIRES",
+        "LAST USED",
+    ]);
+    for token in tokens {
+        table.add_row([
+            token.id,
+            token.name,
+            token
+                .scopes
+                .iter()
+                .map(TokenScope::as_str)
+                .collect::<Vec<_>>()
+                .join(","),
+            token
+                .restriction
+                .map_or_else(|| "-".to_string(), |r| r.to_string()),
+            format_date(token.expires_at),
+            format_date(token.last_used_at),
+        ]);
+    }
+
+    print!("{table}");
+
+    Ok(())
+}
+
+pub async fn revoke(id: &str) -> Result<()> {
+    client()?
+        .revoke_token(id)
+        .await
+        .with_context(|| format!("Failed to revoke API token '{id}'"))?;
+
+    println!("API token {id} has been revoked.");
+
+    Ok(())
+}
+
+fn format_date(time: Option<DateTime<Utc>>) -> String {
+    time.map_or_else(|| "-".to_string(), |t| t.format("%Y-%m-%d").to_string())
+}
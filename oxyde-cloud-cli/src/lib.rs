@@ -1,4 +1,7 @@
 pub mod api_key;
+pub mod client;
 mod commands;
+mod table;
+mod time;
 
 pub use commands::*;
@@ -1,9 +1,19 @@
 mod api_key;
+mod client;
 mod commands;
+mod table;
+mod time;
 
 use anyhow::{Context, Result};
 use clap::{Parser, Subcommand};
+use commands::apps::AppsCommand;
+use commands::deployments::DeploymentsCommand;
+use commands::domains::DomainsCommand;
+use commands::secrets::{SecretScopeArgs, SecretsCommand};
+use commands::teams::{TeamArgs, TeamsCommand};
+use commands::tokens::TokensCommand;
 use oxyde_cloud_common::config::CloudConfig;
+use oxyde_cloud_common::net::{SecretScope, TokenRestriction};
 use std::path::PathBuf;
 
 #[derive(Parser)]
@@ -47,6 +57,63 @@ enum Commands {
     /// Configure how the project should be deployed to the cloud
     DeployConfig,
 
+    /// List, inspect, delete and transfer apps
+    Apps {
+        #[command(subcommand)]
+        command: AppsCommand,
+    },
+
+    /// List and inspect the deployments of an app
+    Deployments {
+        #[command(subcommand)]
+        command: commands::deployments::DeploymentsCommand,
+    },
+
+    /// Serve the app on custom domains
+    Domains {
+        #[command(subcommand)]
+        command: DomainsCommand,
+    },
+
+    /// Roll the app back to an earlier successful deployment
+    Rollback {
+        /// The name of the project to roll back. Defaults to the name from the config in the
+        /// current directory
+        #[arg(short, long)]
+        name: Option<String>,
+
+        /// Sets a custom config file. Defaults to `oxyde-cloud.toml`
+        #[arg(short, long, value_name = "FILE", default_value = "oxyde-cloud.toml")]
+        config: PathBuf,
+
+        /// The id of the deployment to roll back to. Defaults to the last successful deployment
+        /// before the current one
+        #[arg(long, value_name = "ID")]
+        to: Option<String>,
+
+        /// Don't ask for confirmation
+        #[arg(short, long)]
+        yes: bool,
+    },
+
+    /// Manage the members of a team and their roles
+    Teams {
+        #[command(subcommand)]
+        command: TeamsCommand,
+    },
+
+    /// Create, list and revoke API tokens, e.g. for deploying from CI
+    Tokens {
+        #[command(subcommand)]
+        command: TokensCommand,
+    },
+
+    /// Manage the secrets of the Oxyde Cloud Secret Store
+    Secrets {
+        #[command(subcommand)]
+        command: SecretsCommand,
+    },
+
     Log {
         /// The name of the project to get the logs for. Defaults to the name from the config in
         /// the current directory
@@ -56,6 +123,9 @@ enum Commands {
         /// Sets a custom config file. Defaults to `oxyde-cloud.toml`
         #[arg(short, long, value_name = "FILE", default_value = "oxyde-cloud.toml")]
         config: PathBuf,
+
+        #[command(flatten)]
+        args: commands::log::LogArgs,
     },
 }
 
@@ -90,20 +160,201 @@ async fn main() -> Result<()> {
         Commands::DeployConfig => {
             commands::deploy_config::init_deploy_config().context("Deploy config failed")?;
         }
-        Commands::Log { name, config } => {
-            let config = CloudConfig::load(&config).await.ok();
-
-            let name = if let Some(name) = name {
-                name
-            } else if let Some(config) = config {
-                config.app.slug
-            } else {
-                anyhow::bail!(
-                    "If you don't execute this command in a folder with a config you have to provide an app name!"
-                );
-            };
-
-            commands::log::log(&name)
+        Commands::Apps { command } => match command {
+            AppsCommand::List { team } => {
+                commands::apps::list(team.as_deref())
+                    .await
+                    .context("Apps command failed")?;
+            }
+            AppsCommand::Info { name, config } => {
+                let name = app_slug(name, &config).await?;
+
+                commands::apps::info(&name)
+                    .await
+                    .context("Apps command failed")?;
+            }
+            AppsCommand::Delete {
+                name,
+                config,
+                confirm,
+            } => {
+                let name = app_slug(name, &config).await?;
+
+                commands::apps::delete(&name, confirm)
+                    .await
+                    .context("Apps command failed")?;
+            }
+            AppsCommand::Transfer {
+                to,
+                name,
+                config,
+                confirm,
+            } => {
+                let name = app_slug(name, &config).await?;
+
+                commands::apps::transfer(&name, &to, confirm)
+                    .await
+                    .context("Apps command failed")?;
+            }
+        },
+        Commands::Deployments { command } => match command {
+            DeploymentsCommand::List { name, config } => {
+                let name = app_slug(name, &config).await?;
+
+                commands::deployments::list(&name)
+                    .await
+                    .context("Deployments command failed")?;
+            }
+            DeploymentsCommand::Show { id } => {
+                commands::deployments::show(&id)
+                    .await
+                    .context("Deployments command failed")?;
+            }
+        },
+        Commands::Domains { command } => match command {
+            DomainsCommand::Add { domain, app } => {
+                let name = app_slug(app.name, &app.config).await?;
+
+                commands::domains::add(&name, &domain)
+                    .await
+                    .context("Domains command failed")?;
+            }
+            DomainsCommand::Verify { domain, app } => {
+                let name = app_slug(app.name, &app.config).await?;
+
+                commands::domains::verify(&name, &domain)
+                    .await
+                    .context("Domains command failed")?;
+            }
+            DomainsCommand::List { app } => {
+                let name = app_slug(app.name, &app.config).await?;
+
+                commands::domains::list(&name)
+                    .await
+                    .context("Domains command failed")?;
+            }
+            DomainsCommand::Rm { domain, yes, app } => {
+                let name = app_slug(app.name, &app.config).await?;
+
+                commands::domains::rm(&name, &domain, yes)
+                    .await
+                    .context("Domains command failed")?;
+            }
+        },
+        Commands::Rollback {
+            name,
+            config,
+            to,
+            yes,
+        } => {
+            let name = app_slug(name, &config).await?;
+
+            commands::rollback::rollback(&name, to, yes)
+                .await
+                .context("Rollback failed")?;
+        }
+        Commands::Teams { command } => match command {
+            TeamsCommand::Members { team } => {
+                let team = team_slug(team).await?;
+
+                commands::teams::members(&team)
+                    .await
+                    .context("Teams command failed")?;
+            }
+            TeamsCommand::Invite { email, role, team } => {
+                let team = team_slug(team).await?;
+
+                commands::teams::invite(&team, &email, role)
+                    .await
+                    .context("Teams command failed")?;
+            }
+            TeamsCommand::Remove {
+                username,
+                yes,
+                team,
+            } => {
+                let team = team_slug(team).await?;
+
+                commands::teams::remove(&team, &username, yes)
+                    .await
+                    .context("Teams command failed")?;
+            }
+            TeamsCommand::Role {
+                username,
+                role,
+                team,
+            } => {
+                let team = team_slug(team).await?;
+
+                commands::teams::role(&team, &username, role)
+                    .await
+                    .context("Teams command failed")?;
+            }
+        },
+        Commands::Tokens { command } => match command {
+            TokensCommand::Create {
+                name,
+                scopes,
+                app,
+                team,
+                unrestricted,
+                expires,
+                config,
+            } => {
+                let restriction = match (app, team) {
+                    _ if unrestricted => None,
+                    (Some(app), _) => Some(TokenRestriction::App(app)),
+                    (None, Some(team)) => Some(TokenRestriction::Team(team)),
+                    (None, None) => {
+                        let config = CloudConfig::load(&config).await.context(
+                            "Restrict the token with --app or --team, or pass --unrestricted to allow access to everything",
+                        )?;
+                        Some(TokenRestriction::App(config.app.slug))
+                    }
+                };
+
+                commands::tokens::create(&name, scopes, restriction, expires)
+                    .await
+                    .context("Tokens command failed")?;
+            }
+            TokensCommand::List => {
+                commands::tokens::list()
+                    .await
+                    .context("Tokens command failed")?;
+            }
+            TokensCommand::Revoke { id } => {
+                commands::tokens::revoke(&id)
+                    .await
+                    .context("Tokens command failed")?;
+            }
+        },
+        Commands::Secrets { command } => match command {
+            SecretsCommand::Set { name, scope } => {
+                let scope = secret_scope(scope).await?;
+
+                commands::secrets::set(&scope, &name)
+                    .await
+                    .context("Secrets command failed")?;
+            }
+            SecretsCommand::List { scope } => {
+                let scope = secret_scope(scope).await?;
+
+                commands::secrets::list(&scope)
+                    .await
+                    .context("Secrets command failed")?;
+            }
+            SecretsCommand::Rm { name, scope } => {
+                let scope = secret_scope(scope).await?;
+
+                commands::secrets::rm(&scope, &name)
+                    .await
+                    .context("Secrets command failed")?;
+            }
+        },
+        Commands::Log { name, config, args } => {
+            let name = app_slug(name, &config).await?;
+
+            commands::log::log(&name, args)
                 .await
                 .context("Log command failed")?;
         }
@@ -111,3 +362,39 @@ async fn main() -> Result<()> {
 
     Ok(())
 }
+
+/// The app name given on the command line or else the one from the config.
+async fn app_slug(name: Option<String>, config: &PathBuf) -> Result<String> {
+    if let Some(name) = name {
+        return Ok(name);
+    }
+
+    match CloudConfig::load(config).await {
+        Ok(config) => Ok(config.app.slug),
+        Err(_) => anyhow::bail!(
+            "If you don't execute this command in a folder with a config you have to provide an app name!"
+        ),
+    }
+}
+
+/// The team given on the command line or else the app like [`app_slug`].
+async fn secret_scope(args: SecretScopeArgs) -> Result<SecretScope> {
+    match args.team {
+        Some(team) => Ok(SecretScope::Team(team)),
+        None => Ok(SecretScope::App(app_slug(args.app, &args.config).await?)),
+    }
+}
+
+/// The team given on the command line or else the one owning the app from the config.
+async fn team_slug(args: TeamArgs) -> Result<String> {
+    if let Some(team) = args.team {
+        return Ok(team);
+    }
+
+    match CloudConfig::load(&args.config).await {
+        Ok(config) => commands::teams::team_of_app(&config.app.slug).await,
+        Err(_) => anyhow::bail!(
+            "If you don't execute this command in a folder with a config you have to provide a team with --team!"
+        ),
+    }
+}
@@ -0,0 +1,38 @@
+use chrono::{DateTime, TimeDelta, Utc};
+use oxyde_cloud_common::duration::parse_duration;
+
+/// Whether a duration counts back or forward from now.
+#[derive(Debug, Clone, Copy)]
+pub enum Direction {
+    Past,
+    Future,
+}
+
+/// Parses an RFC 3339 timestamp or a duration like `15m` relative to now in the given
+/// direction.
+pub fn parse_relative_time(value: &str, direction: Direction) -> Result<DateTime<Utc>, String> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Ok(time.with_timezone(&Utc));
+    }
+
+    let invalid = || {
+        let (timestamp, duration) = match direction {
+            Direction::Past => ("2025-01-31T12:00:00Z", "15m"),
+            Direction::Future => ("2026-01-31T00:00:00Z", "90d"),
+        };
+
+        format!(
+            "`{value}` is neither a timestamp like `{timestamp}` nor a duration like `{duration}`"
+        )
+    };
+
+    let now = Utc::now();
+
+    parse_duration(value)
+        .and_then(|duration| TimeDelta::from_std(duration).ok())
+        .and_then(|duration| match direction {
+            Direction::Past => now.checked_sub_signed(duration),
+            Direction::Future => now.checked_add_signed(duration),
+        })
+        .ok_or_else(invalid)
+}
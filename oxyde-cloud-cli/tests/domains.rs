@@ -0,0 +1,10 @@
+use oxyde_cloud_cli::domains::add;
+
+#[tokio::test]
+async fn rejects_invalid_domains_before_sending_them() {
+    // These fail before the API key is looked up, so no request is sent.
+    for domain in ["example", "Example.com", "-example.com", "exa mple.com", ""] {
+        let err = add("my-app", domain).await.unwrap_err();
+        assert!(err.to_string().starts_with("Invalid domain"), "{err:#}");
+    }
+}
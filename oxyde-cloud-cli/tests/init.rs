@@ -0,0 +1,116 @@
+use std::collections::VecDeque;
+
+use anyhow::Result;
+use oxyde_cloud_cli::client::client_with_config;
+use oxyde_cloud_cli::init::app_slug::input_app_slug;
+use oxyde_cloud_cli::init::prompt::Prompt;
+use oxyde_cloud_cli::init::team::input_team_slug;
+use oxyde_cloud_client::Client;
+use oxyde_cloud_common::net::Team;
+use oxyde_cloud_testkit::FakeCloud;
+
+/// Gives the answers of a user in order and panics on questions it has no answer for.
+#[derive(Default)]
+struct Scripted {
+    new_team_slugs: VecDeque<&'static str>,
+    team_names: VecDeque<&'static str>,
+    selected_teams: VecDeque<&'static str>,
+    app_slugs: VecDeque<&'static str>,
+}
+
+fn answer(answers: &mut VecDeque<&'static str>, question: &str) -> Result<String> {
+    let answer = answers.pop_front();
+    Ok(answer
+        .unwrap_or_else(|| panic!("unexpected question for {question}"))
+        .to_string())
+}
+
+impl Prompt for Scripted {
+    fn new_team_slug(&mut self) -> Result<String> {
+        answer(&mut self.new_team_slugs, "a new team slug")
+    }
+
+    fn team_name(&mut self, _default: &str) -> Result<String> {
+        answer(&mut self.team_names, "a team name")
+    }
+
+    fn select_team(&mut self, _teams: &[Team]) -> Result<String> {
+        answer(&mut self.selected_teams, "a team")
+    }
+
+    fn app_slug(&mut self) -> Result<String> {
+        answer(&mut self.app_slugs, "an app slug")
+    }
+}
+
+fn client(cloud: &FakeCloud) -> Client {
+    client_with_config(cloud.client_config(), cloud.api_key().to_string()).unwrap()
+}
+
+#[tokio::test]
+async fn creates_a_team_for_users_without_one() {
+    let cloud = FakeCloud::start().await;
+    let mut prompt = Scripted {
+        new_team_slugs: ["my-team"].into(),
+        team_names: [""].into(),
+        ..Scripted::default()
+    };
+
+    let team_slug = input_team_slug(&client(&cloud), &mut prompt).await.unwrap();
+
+    assert_eq!(team_slug, "my-team");
+    // An empty name defaults to the slug in title case.
+    assert_eq!(cloud.state().teams["my-team"], "My Team");
+}
+
+#[tokio::test]
+async fn uses_the_only_team_without_asking() {
+    let cloud = FakeCloud::start().await;
+    cloud.state().add_team("my-team", "My Team");
+
+    let team_slug = input_team_slug(&client(&cloud), &mut Scripted::default())
+        .await
+        .unwrap();
+
+    assert_eq!(team_slug, "my-team");
+}
+
+#[tokio::test]
+async fn lets_users_with_several_teams_choose() {
+    let cloud = FakeCloud::start().await;
+    cloud
+        .state()
+        .add_team("my-team", "My Team")
+        .add_team("other-team", "Other Team");
+    let mut prompt = Scripted {
+        selected_teams: ["other-team"].into(),
+        ..Scripted::default()
+    };
+
+    let team_slug = input_team_slug(&client(&cloud), &mut prompt).await.unwrap();
+
+    assert_eq!(team_slug, "other-team");
+}
+
+#[tokio::test]
+async fn asks_for_app_slugs_until_one_is_available() {
+    let cloud = FakeCloud::start().await;
+    {
+        let mut state = cloud.state();
+        state.add_team("my-team", "My Team");
+        state.add_app("taken-app", "other-team");
+    }
+    let mut prompt = Scripted {
+        app_slugs: ["taken-app", "my-app"].into(),
+        ..Scripted::default()
+    };
+
+    let app_slug = input_app_slug(&client(&cloud), "my-team", &mut prompt)
+        .await
+        .unwrap();
+
+    assert_eq!(app_slug, "my-app");
+    let state = cloud.state();
+    assert_eq!(state.apps["my-app"], "my-team");
+    assert_eq!(state.hits("apps/new"), 2);
+}
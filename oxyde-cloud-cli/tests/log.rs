@@ -0,0 +1,74 @@
+use chrono::{DateTime, TimeDelta, Utc};
+use clap::Parser;
+use clap::error::ErrorKind;
+use oxyde_cloud_cli::log::{LogArgs, LogFormat, format_entry};
+use oxyde_cloud_common::net::{LogEntry, LogLevel, OutputStream};
+
+#[derive(Parser)]
+struct Cli {
+    #[command(flatten)]
+    log: LogArgs,
+}
+
+fn parse(args: &[&str]) -> Result<LogArgs, clap::Error> {
+    Cli::try_parse_from(std::iter::once("oxy").chain(args.iter().copied())).map(|cli| cli.log)
+}
+
+#[test]
+fn parses_durations_ago() {
+    let args = parse(&["--since", "15m", "--until", "2025-01-31T12:00:00Z"]).unwrap();
+
+    let ago = Utc::now() - args.since.unwrap();
+    assert!(ago >= TimeDelta::minutes(15) && ago < TimeDelta::minutes(16));
+    assert_eq!(
+        args.until.unwrap().to_rfc3339(),
+        "2025-01-31T12:00:00+00:00"
+    );
+
+    for invalid in ["15", "15w", "yesterday", "99999999999999d"] {
+        let err = parse(&["--since", invalid]).err().unwrap();
+        assert_eq!(err.kind(), ErrorKind::ValueValidation, "{invalid}");
+    }
+}
+
+#[test]
+fn follows_with_filters_and_formats() {
+    let args = parse(&[
+        "--follow", "--level", "warn", "--grep", "/api", "--format", "json",
+    ])
+    .unwrap();
+    assert!(args.follow);
+    assert_eq!(args.grep.as_deref(), Some("/api"));
+
+    // A followed log has no end.
+    let err = parse(&["--follow", "--until", "15m"]).err().unwrap();
+    assert_eq!(err.kind(), ErrorKind::ArgumentConflict);
+}
+
+#[test]
+fn formats_entries() {
+    let entry = LogEntry {
+        timestamp: DateTime::parse_from_rfc3339("2025-01-31T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc),
+        level: LogLevel::Warn,
+        stream: OutputStream::Stderr,
+        instance: "instance-1".to_string(),
+        message: "Slow request to /api".to_string(),
+        cursor: Some("7".to_string()),
+    };
+
+    assert_eq!(
+        format_entry(&entry, LogFormat::Text).unwrap(),
+        "Slow request to /api"
+    );
+    assert_eq!(
+        format_entry(&entry, LogFormat::Full).unwrap(),
+        "2025-01-31T12:00:00.000Z  WARN instance-1 stderr: Slow request to /api"
+    );
+    assert!(
+        format_entry(&entry, LogFormat::Json)
+            .unwrap()
+            .contains(r#""message":"Slow request to /api""#)
+    );
+}
@@ -0,0 +1,25 @@
+use oxyde_cloud_cli::client::client_with_config;
+use oxyde_cloud_client::ClientError;
+use oxyde_cloud_testkit::FakeCloud;
+
+#[tokio::test]
+async fn logs_in_with_the_received_api_key() {
+    let cloud = FakeCloud::start().await;
+    cloud.state().username = "jane".to_string();
+
+    let login = client_with_config(cloud.client_config(), cloud.api_key().to_string())
+        .unwrap()
+        .login()
+        .await
+        .unwrap();
+    assert_eq!(login.username, "jane");
+
+    // The API rejects keys it didn't hand out.
+    let err = client_with_config(cloud.client_config(), "wrong".to_string())
+        .unwrap()
+        .login()
+        .await
+        .unwrap_err();
+    assert!(matches!(err, ClientError::Unauthorized { .. }), "{err:?}");
+    assert_eq!(cloud.state().hits("login"), 2);
+}
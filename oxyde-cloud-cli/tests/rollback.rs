@@ -0,0 +1,91 @@
+use chrono::Utc;
+use oxyde_cloud_cli::rollback::previous_successful;
+use oxyde_cloud_common::net::{Deployment, DeploymentStatus};
+
+fn deployment(id: &str, status: DeploymentStatus, rolled_back_from: Option<&str>) -> Deployment {
+    Deployment {
+        id: id.to_string(),
+        app_slug: "my-app".to_string(),
+        status,
+        created_at: Utc::now(),
+        creator: "tester".to_string(),
+        commit_sha: None,
+        size: 0,
+        rolled_back_from: rolled_back_from.map(str::to_string),
+    }
+}
+
+fn target(deployments: &[Deployment]) -> Option<&str> {
+    previous_successful(deployments).map(|d| d.id.as_str())
+}
+
+#[test]
+fn picks_the_previous_successful_deployment() {
+    use DeploymentStatus::*;
+
+    let deployments = [
+        deployment("v3", Success, None),
+        deployment("v2", Failure, None),
+        deployment("v1", Success, None),
+    ];
+    assert_eq!(target(&deployments), Some("v1"));
+
+    assert_eq!(target(&deployments[..1]), None);
+    assert_eq!(target(&[]), None);
+}
+
+#[test]
+fn ignores_pending_deployments() {
+    use DeploymentStatus::*;
+
+    let deployments = [
+        deployment("v3", Pending, None),
+        deployment("v2", Success, None),
+        deployment("v1", Success, None),
+    ];
+    assert_eq!(target(&deployments), Some("v1"));
+}
+
+#[test]
+fn goes_further_back_after_a_rollback() {
+    use DeploymentStatus::*;
+
+    // v3 was rolled back to v2. Rolling back again must not return to v3.
+    let deployments = [
+        deployment("r1", Success, Some("v2")),
+        deployment("v3", Success, None),
+        deployment("v2", Success, None),
+        deployment("v1", Success, None),
+    ];
+    assert_eq!(target(&deployments), Some("v1"));
+
+    // And once more from the copy of v1.
+    let deployments = [
+        deployment("r2", Success, Some("v1")),
+        deployment("r1", Success, Some("v2")),
+        deployment("v3", Success, None),
+        deployment("v2", Success, None),
+        deployment("v1", Success, None),
+        deployment("v0", Success, None),
+    ];
+    assert_eq!(target(&deployments), Some("v0"));
+}
+
+#[test]
+fn skips_copies_of_the_current_release() {
+    use DeploymentStatus::*;
+
+    // v2 was rolled back to twice, and the original is no longer listed.
+    let deployments = [
+        deployment("r2", Success, Some("v2")),
+        deployment("r1", Success, Some("v2")),
+        deployment("v1", Success, None),
+    ];
+    assert_eq!(target(&deployments), Some("v1"));
+
+    let deployments = [
+        deployment("r1", Success, Some("v1")),
+        deployment("v1", Success, None),
+    ];
+    assert_eq!(target(&deployments), None);
+}
@@ -0,0 +1,45 @@
+use oxyde_cloud_common::net::{App, DeleteAppRequest, SuccessResponse, TransferAppRequest};
+
+use crate::{Client, Result, path_segment};
+
+impl Client {
+    /// Lists the apps of all teams the user is a member of.
+    pub async fn apps(&self) -> Result<Vec<App>> {
+        self.get("apps").send().await
+    }
+
+    pub async fn app(&self, app_slug: &str) -> Result<App> {
+        self.get(&format!("apps/{}", path_segment(app_slug)))
+            .send()
+            .await
+    }
+
+    /// Deletes an app together with its deployments, logs and secrets. This can't be undone.
+    pub async fn delete_app(&self, app_slug: &str) -> Result<()> {
+        let _: SuccessResponse = self
+            .post("apps/delete")
+            .json(&DeleteAppRequest {
+                app_slug: app_slug.to_string(),
+            })?
+            .send()
+            .await?;
+
+        Ok(())
+    }
+
+    /// Moves an app to the team `team_slug`. Team secrets of the old team are no longer
+    /// available to the app afterwards.
+    pub async fn transfer_app(&self, app_slug: &str, team_slug: &str) -> Result<()> {
+        let _: SuccessResponse = self
+            .post("apps/transfer")
+            .idempotent()
+            .json(&TransferAppRequest {
+                app_slug: app_slug.to_string(),
+                team_slug: team_slug.to_string(),
+            })?
+            .send()
+            .await?;
+
+        Ok(())
+    }
+}
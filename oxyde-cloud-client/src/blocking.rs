@@ -0,0 +1,390 @@
+//! A synchronous client for build scripts, `xtask` binaries and other code without an async
+//! runtime. Requires the `blocking` feature.
+//!
+//! [`Client`] has the same methods as the async [`crate::Client`] and runs them to completion
+//! on an internal single threaded Tokio runtime. Like with `reqwest::blocking` its methods must
+//! not be called from within an async runtime, where they panic.
+//!
+//! ```no_run
+//! # fn example() -> oxyde_cloud_client::Result<()> {
+//! use oxyde_cloud_client::blocking::Client;
+//!
+//! let client = Client::from_env("my-api-key".to_string());
+//!
+//! for deployment in client.deployments("my-app")? {
+//!     println!("{} {}", deployment.id, deployment.status);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::io::{ErrorKind, Read};
+use std::path::Path;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+
+use bytes::Bytes;
+use futures_util::stream::{self, BoxStream};
+use futures_util::{Stream, StreamExt};
+use http::{HeaderName, HeaderValue};
+use oxyde_cloud_common::config::CloudConfig;
+use oxyde_cloud_common::net::{
+    ApiToken, App, DeployManifest, Deployment, Domain, LogEntry, LogRequest, LogStreamRequest,
+    LoginResponse, ManifestResponse, NewBlobRequest, NewTokenRequest, NewTokenResponse,
+    NewUploadSessionRequest, Secret, SecretScope, Team, TeamInvitation, TeamMember, TeamRole,
+    UploadSession,
+};
+use reqwest::multipart::Form;
+use serde::{Deserialize, Serialize};
+use tokio::runtime::Runtime;
+use tokio::sync::mpsc;
+use tokio_util::io::StreamReader;
+
+use crate::{ClientConfig, ClientError, Result, UploadOptions};
+
+/// Blocking client for the Oxyde Cloud API.
+///
+/// Cloning it is cheap since the configuration, the connection pool and the runtime are shared.
+#[derive(Clone)]
+pub struct Client {
+    inner: crate::Client,
+    runtime: Arc<Runtime>,
+}
+
+/// Generates methods that block on the async method of the same name.
+macro_rules! blocking_methods {
+    ($(fn $name:ident(&self $(, $arg:ident: $ty:ty)*) -> $ret:ty;)*) => {
+        $(
+            #[doc = concat!("Blocking version of [`Client::", stringify!($name), "`](crate::Client::", stringify!($name), ").")]
+            pub fn $name(&self $(, $arg: $ty)*) -> Result<$ret> {
+                self.runtime.block_on(self.inner.$name($($arg),*))
+            }
+        )*
+    };
+}
+
+impl Client {
+    /// Creates a client for the Oxyde Cloud API with the default [`ClientConfig`].
+    pub fn new(api_key: String) -> Self {
+        ClientConfig::default()
+            .build_blocking(api_key)
+            .expect("default client config is valid")
+    }
+
+    /// Creates a client with [`ClientConfig::from_env`], i.e. the base URL can be overridden
+    /// with the `OXYDE_CLOUD_API_URL` environment variable.
+    pub fn from_env(api_key: String) -> Self {
+        ClientConfig::from_env()
+            .build_blocking(api_key)
+            .expect("client config from env is valid")
+    }
+
+    /// Starts configuring a client. Finish with [`ClientConfig::build_blocking`].
+    pub fn builder() -> ClientConfig {
+        ClientConfig::default()
+    }
+
+    pub(crate) fn with_runtime(inner: crate::Client) -> Result<Self> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(ClientError::Runtime)?;
+
+        Ok(Self {
+            inner,
+            runtime: Arc::new(runtime),
+        })
+    }
+
+    /// The async client this client drives.
+    pub fn as_async(&self) -> &crate::Client {
+        &self.inner
+    }
+
+    blocking_methods! {
+        fn teams(&self) -> Vec<Team>;
+        fn new_app(&self, app_slug: &str, team_slug: &str, name: &str) -> bool;
+        fn new_team(&self, team_slug: &str) -> bool;
+        fn set_team_name(&self, team_slug: &str, team_name: &str) -> ();
+        fn login(&self) -> LoginResponse;
+        fn submit_manifest(&self, manifest: &DeployManifest) -> ManifestResponse;
+        fn upload_done(&self, config: &CloudConfig) -> String;
+        fn log(&self, request: &LogRequest) -> Vec<LogEntry>;
+
+        fn apps(&self) -> Vec<App>;
+        fn app(&self, app_slug: &str) -> App;
+        fn delete_app(&self, app_slug: &str) -> ();
+        fn transfer_app(&self, app_slug: &str, team_slug: &str) -> ();
+
+        fn deployments(&self, app_slug: &str) -> Vec<Deployment>;
+        fn deployment(&self, id: &str) -> Deployment;
+        fn rollback(&self, app_slug: &str, deployment_id: &str) -> String;
+        fn wait_for_deployment(&self, id: &str, timeout: Duration) -> Deployment;
+
+        fn add_domain(&self, app_slug: &str, domain: &str) -> Domain;
+        fn domains(&self, app_slug: &str) -> Vec<Domain>;
+        fn domain(&self, app_slug: &str, domain: &str) -> Domain;
+        fn verify_domain(&self, app_slug: &str, domain: &str) -> Domain;
+        fn remove_domain(&self, app_slug: &str, domain: &str) -> ();
+
+        fn set_secret(&self, scope: &SecretScope, name: &str, value: &str) -> ();
+        fn list_secrets(&self, scope: &SecretScope) -> Vec<Secret>;
+        fn delete_secret(&self, scope: &SecretScope, name: &str) -> ();
+        fn missing_secrets(&self, app_slug: &str, names: &[String]) -> Vec<String>;
+
+        fn team_members(&self, team_slug: &str) -> Vec<TeamMember>;
+        fn team_invitations(&self, team_slug: &str) -> Vec<TeamInvitation>;
+        fn invite_member(&self, team_slug: &str, email: &str, role: TeamRole) -> TeamInvitation;
+        fn remove_member(&self, team_slug: &str, username: &str) -> ();
+        fn set_member_role(&self, team_slug: &str, username: &str, role: TeamRole) -> ();
+
+        fn create_token(&self, request: &NewTokenRequest) -> NewTokenResponse;
+        fn list_tokens(&self) -> Vec<ApiToken>;
+        fn revoke_token(&self, id: &str) -> ();
+
+        fn upload_file(&self, app_slug: impl AsRef<str>, path: impl AsRef<Path>) -> ();
+        fn upload_file_with_options(
+            &self,
+            app_slug: impl AsRef<str>,
+            path: impl AsRef<Path>,
+            options: &UploadOptions
+        ) -> ();
+        fn upload_blob_with_options(
+            &self,
+            app_slug: impl AsRef<str>,
+            path: impl AsRef<Path>,
+            sha256: &str,
+            options: &UploadOptions
+        ) -> ();
+        fn new_upload_session(&self, request: NewUploadSessionRequest) -> UploadSession;
+        fn new_blob_session(&self, request: NewBlobRequest) -> UploadSession;
+        fn upload_session(&self, session_id: &str) -> UploadSession;
+    }
+
+    /// Blocking version of [`Client::upload_reader`](crate::Client::upload_reader).
+    pub fn upload_reader<R>(
+        &self,
+        app_slug: impl AsRef<str>,
+        file_name: impl AsRef<str>,
+        reader: R,
+        len: u64,
+    ) -> Result<()>
+    where
+        R: Read + Send,
+    {
+        self.upload_reader_with_options(app_slug, file_name, reader, len, &UploadOptions::default())
+    }
+
+    /// Blocking version of
+    /// [`Client::upload_reader_with_options`](crate::Client::upload_reader_with_options).
+    pub fn upload_reader_with_options<R>(
+        &self,
+        app_slug: impl AsRef<str>,
+        file_name: impl AsRef<str>,
+        reader: R,
+        len: u64,
+        options: &UploadOptions,
+    ) -> Result<()>
+    where
+        R: Read + Send,
+    {
+        self.block_on_upload(reader, len, |reader| {
+            self.inner
+                .upload_reader_with_options(app_slug, file_name, reader, len, options)
+        })
+    }
+
+    /// Blocking version of [`Client::upload_blob_reader`](crate::Client::upload_blob_reader).
+    pub fn upload_blob_reader<R>(
+        &self,
+        app_slug: impl AsRef<str>,
+        sha256: &str,
+        reader: R,
+        len: u64,
+    ) -> Result<()>
+    where
+        R: Read + Send,
+    {
+        self.upload_blob_reader_with_options(
+            app_slug,
+            sha256,
+            reader,
+            len,
+            &UploadOptions::default(),
+        )
+    }
+
+    /// Blocking version of
+    /// [`Client::upload_blob_reader_with_options`](crate::Client::upload_blob_reader_with_options).
+    pub fn upload_blob_reader_with_options<R>(
+        &self,
+        app_slug: impl AsRef<str>,
+        sha256: &str,
+        reader: R,
+        len: u64,
+        options: &UploadOptions,
+    ) -> Result<()>
+    where
+        R: Read + Send,
+    {
+        self.block_on_upload(reader, len, |reader| {
+            self.inner
+                .upload_blob_reader_with_options(app_slug, sha256, reader, len, options)
+        })
+    }
+
+    /// Runs an upload of the first `len` bytes of `reader`. They're read on a separate thread, so
+    /// blocking reads don't hold up the runtime and with it the timeouts of the upload.
+    fn block_on_upload<R, F>(
+        &self,
+        reader: R,
+        len: u64,
+        upload: impl FnOnce(ThreadReader) -> F,
+    ) -> Result<()>
+    where
+        R: Read + Send,
+        F: Future<Output = Result<()>>,
+    {
+        let (tx, rx) = mpsc::channel(READ_AHEAD_CHUNKS);
+        let chunks = stream::unfold(rx, |mut rx| async move {
+            rx.recv().await.map(|chunk| (chunk, rx))
+        });
+
+        std::thread::scope(|scope| {
+            scope.spawn(move || read_chunks(reader.take(len), tx));
+            self.runtime
+                .block_on(upload(StreamReader::new(chunks.boxed())))
+        })
+    }
+
+    /// Blocking version of [`Client::log_stream`](crate::Client::log_stream). Every call to
+    /// [`Iterator::next`] blocks until the next entry arrives.
+    pub fn log_stream(&self, request: &LogStreamRequest) -> LogEntries {
+        LogEntries {
+            runtime: Arc::clone(&self.runtime),
+            stream: Box::pin(self.inner.log_stream(request)),
+        }
+    }
+
+    /// Blocking version of [`Client::post`](crate::Client::post).
+    pub fn post(&self, route: &str) -> ClientBuilder {
+        ClientBuilder {
+            inner: self.inner.post(route),
+            runtime: Arc::clone(&self.runtime),
+        }
+    }
+
+    /// Blocking version of [`Client::get`](crate::Client::get).
+    pub fn get(&self, route: &str) -> ClientBuilder {
+        ClientBuilder {
+            inner: self.inner.get(route),
+            runtime: Arc::clone(&self.runtime),
+        }
+    }
+}
+
+/// The entries of a followed log, returned by [`Client::log_stream`].
+pub struct LogEntries {
+    runtime: Arc<Runtime>,
+    stream: Pin<Box<dyn Stream<Item = Result<LogEntry>> + Send>>,
+}
+
+impl Iterator for LogEntries {
+    type Item = Result<LogEntry>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.runtime.block_on(self.stream.next())
+    }
+}
+
+/// Blocking version of [`ClientBuilder`](crate::ClientBuilder) for requests to routes the
+/// client has no method for.
+pub struct ClientBuilder {
+    inner: crate::ClientBuilder,
+    runtime: Arc<Runtime>,
+}
+
+impl ClientBuilder {
+    fn map(self, f: impl FnOnce(crate::ClientBuilder) -> crate::ClientBuilder) -> Self {
+        Self {
+            inner: f(self.inner),
+            ..self
+        }
+    }
+
+    /// See [`ClientBuilder::idempotent`](crate::ClientBuilder::idempotent).
+    pub fn idempotent(self) -> Self {
+        self.map(|b| b.idempotent())
+    }
+
+    /// See [`ClientBuilder::idempotency_key`](crate::ClientBuilder::idempotency_key).
+    pub fn idempotency_key(self, key: impl AsRef<str>) -> Self {
+        self.map(|b| b.idempotency_key(key))
+    }
+
+    pub fn auth_header(self, api_key: &str) -> Self {
+        self.map(|b| b.auth_header(api_key))
+    }
+
+    pub fn body<T: Into<reqwest::Body>>(self, body: T) -> Self {
+        self.map(|b| b.body(body))
+    }
+
+    pub fn multipart(self, form: Form) -> Self {
+        self.map(|b| b.multipart(form))
+    }
+
+    pub fn json<Body: Serialize>(self, json: Body) -> Result<ClientBuilder> {
+        Ok(Self {
+            inner: self.inner.json(json)?,
+            runtime: self.runtime,
+        })
+    }
+
+    pub fn header<K, V>(self, key: K, value: V) -> Self
+    where
+        HeaderName: TryFrom<K>,
+        <HeaderName as TryFrom<K>>::Error: Into<http::Error>,
+        HeaderValue: TryFrom<V>,
+        <HeaderValue as TryFrom<V>>::Error: Into<http::Error>,
+    {
+        self.map(|b| b.header(key, value))
+    }
+
+    pub fn send<Resp>(self) -> Result<Resp>
+    where
+        for<'de> Resp: Deserialize<'de>,
+    {
+        self.runtime.block_on(self.inner.send())
+    }
+}
+
+/// The end of the channel the reader thread of [`Client::block_on_upload`] sends to.
+type ThreadReader = StreamReader<BoxStream<'static, std::io::Result<Bytes>>, Bytes>;
+
+/// Number of chunks the reader thread reads ahead of the upload.
+const READ_AHEAD_CHUNKS: usize = 4;
+const READ_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Sends the contents of `reader` in chunks until its end, its first error or until the upload
+/// hangs up.
+fn read_chunks(mut reader: impl Read, tx: mpsc::Sender<std::io::Result<Bytes>>) {
+    loop {
+        let mut buf = vec![0; READ_CHUNK_SIZE];
+        let chunk = match reader.read(&mut buf) {
+            Ok(0) => return,
+            Ok(n) => {
+                buf.truncate(n);
+                Ok(Bytes::from(buf))
+            }
+            Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+            Err(err) => Err(err),
+        };
+
+        let failed = chunk.is_err();
+        if tx.blocking_send(chunk).is_err() || failed {
+            return;
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use std::time::Duration;
+
+use log::debug;
+use oxyde_cloud_common::net::{
+    Deployment, DeploymentStatus, LogEntry, LogRequest, RollbackRequest, RollbackResponse,
+};
+
+use crate::time::{self, Instant};
+use crate::{Client, ClientError, Result, path_segment};
+
+/// How many log entries are attached to [`ClientError::DeploymentFailed`].
+const STARTUP_LOG_LINES: usize = 20;
+
+const MIN_POLL_INTERVAL: Duration = Duration::from_millis(250);
+const MAX_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+impl Client {
+    /// Lists the deployments of an app, newest first.
+    pub async fn deployments(&self, app_slug: &str) -> Result<Vec<Deployment>> {
+        self.get(&format!("apps/{}/deployments", path_segment(app_slug)))
+            .send()
+            .await
+    }
+
+    pub async fn deployment(&self, id: &str) -> Result<Deployment> {
+        self.get(&format!("deployments/{}", path_segment(id)))
+            .send()
+            .await
+    }
+
+    /// Rolls an app back to an earlier successful deployment by redeploying its files. Returns
+    /// the id of the new deployment to pass to [`Client::wait_for_deployment`].
+    pub async fn rollback(&self, app_slug: &str, deployment_id: &str) -> Result<String> {
+        let res: RollbackResponse = self
+            .post("apps/rollback")
+            .json(&RollbackRequest {
+                app_slug: app_slug.to_string(),
+                deployment_id: deployment_id.to_string(),
+            })?
+            .send()
+            .await?;
+
+        Ok(res.deployment_id)
+    }
+
+    /// Polls the status of a deployment until it's no longer pending.
+    ///
+    /// Returns the deployment once it's live. If it failed, the error is
+    /// [`ClientError::DeploymentFailed`] with the last log entries written since the deployment
+    /// started, which usually tell why the server didn't come up. If it's still pending after
+    /// `timeout`, the error is [`ClientError::DeploymentTimeout`].
+    pub async fn wait_for_deployment(&self, id: &str, timeout: Duration) -> Result<Deployment> {
+        let deadline = Instant::now() + timeout;
+        let mut interval = MIN_POLL_INTERVAL;
+
+        loop {
+            let deployment = self.deployment(id).await?;
+
+            match deployment.status {
+                DeploymentStatus::Success => return Ok(deployment),
+                DeploymentStatus::Failure => {
+                    let startup_log = self.startup_log(&deployment).await;
+
+                    return Err(ClientError::DeploymentFailed {
+                        deployment: Box::new(deployment),
+                        startup_log,
+                    });
+                }
+                DeploymentStatus::Pending => {}
+            }
+
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(ClientError::DeploymentTimeout {
+                    id: id.to_string(),
+                    timeout,
+                });
+            }
+
+            debug!("Deployment {id} is still pending");
+            time::sleep(interval.min(deadline - now)).await;
+            interval = (interval * 2).min(MAX_POLL_INTERVAL);
+        }
+    }
+
+    /// The last log entries since `deployment` was created. Failing to fetch them shouldn't hide
+    /// the failed deployment, so errors only result in an empty log.
+    async fn startup_log(&self, deployment: &Deployment) -> Vec<LogEntry> {
+        let request = LogRequest {
+            since: Some(deployment.created_at),
+            tail: Some(STARTUP_LOG_LINES),
+            ..LogRequest::new(&deployment.app_slug)
+        };
+
+        match self.log(&request).await {
+            Ok(entries) => entries,
+            Err(err) => {
+                debug!(
+                    "Failed to fetch startup log of deployment {}: {err}",
+                    deployment.id
+                );
+                Vec::new()
+            }
+        }
+    }
+}
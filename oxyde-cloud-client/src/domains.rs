@@ -0,0 +1,60 @@
+use oxyde_cloud_common::net::{Domain, DomainRequest, SuccessResponse};
+
+use crate::{Client, Result, path_segment};
+
+impl Client {
+    /// Adds a custom domain to an app. The returned [`Domain::records`] have to be set up at the
+    /// DNS provider before [`Client::verify_domain`] succeeds.
+    pub async fn add_domain(&self, app_slug: &str, domain: &str) -> Result<Domain> {
+        self.post("apps/domains/new")
+            .json(&DomainRequest {
+                app_slug: app_slug.to_string(),
+                domain: domain.to_string(),
+            })?
+            .send()
+            .await
+    }
+
+    pub async fn domains(&self, app_slug: &str) -> Result<Vec<Domain>> {
+        self.get(&format!("apps/{}/domains", path_segment(app_slug)))
+            .send()
+            .await
+    }
+
+    /// Fetches a custom domain with its required DNS records and certificate status.
+    pub async fn domain(&self, app_slug: &str, domain: &str) -> Result<Domain> {
+        self.get(&format!(
+            "apps/{}/domains/{}",
+            path_segment(app_slug),
+            path_segment(domain)
+        ))
+        .send()
+        .await
+    }
+
+    /// Checks the DNS records of a custom domain. If they're in place the domain is verified and
+    /// a certificate is requested, otherwise it stays pending.
+    pub async fn verify_domain(&self, app_slug: &str, domain: &str) -> Result<Domain> {
+        self.post("apps/domains/verify")
+            .idempotent()
+            .json(&DomainRequest {
+                app_slug: app_slug.to_string(),
+                domain: domain.to_string(),
+            })?
+            .send()
+            .await
+    }
+
+    pub async fn remove_domain(&self, app_slug: &str, domain: &str) -> Result<()> {
+        let _: SuccessResponse = self
+            .post("apps/domains/remove")
+            .json(&DomainRequest {
+                app_slug: app_slug.to_string(),
+                domain: domain.to_string(),
+            })?
+            .send()
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,255 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use oxyde_cloud_common::net::{Deployment, ErrorResponse, LogEntry};
+use reqwest::StatusCode;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use thiserror::Error;
+
+use crate::time::SystemTime;
+
+pub type Result<T, E = ClientError> = std::result::Result<T, E>;
+
+/// Everything that can go wrong when talking to the Oxyde Cloud API.
+///
+/// Every variant that stems from a request carries the `route` it was sent to. Variants for
+/// unsuccessful HTTP responses additionally carry the [`ErrorResponse`] sent by the server, if
+/// it could be read.
+///
+/// `ClientError` implements [`std::error::Error`] so it converts into `anyhow::Error` with `?`
+/// and can be recovered from there with `downcast_ref::<ClientError>()`.
+#[derive(Debug, Error)]
+pub enum ClientError {
+    /// The API key is missing, invalid or has been revoked (401 / 403).
+    #[error("{}", status_message(*status, route, error))]
+    Unauthorized {
+        status: StatusCode,
+        route: String,
+        error: Option<ErrorResponse>,
+    },
+
+    /// The requested resource doesn't exist (404).
+    #[error("{}", status_message(StatusCode::NOT_FOUND, route, error))]
+    NotFound {
+        route: String,
+        error: Option<ErrorResponse>,
+    },
+
+    /// The request conflicts with the current state, e.g. a slug is already taken (409).
+    #[error("{}", status_message(StatusCode::CONFLICT, route, error))]
+    Conflict {
+        route: String,
+        error: Option<ErrorResponse>,
+    },
+
+    /// Too many requests (429). `retry_after` is taken from the `Retry-After` header.
+    #[error("{}", status_message(StatusCode::TOO_MANY_REQUESTS, route, error))]
+    RateLimited {
+        route: String,
+        retry_after: Option<Duration>,
+        error: Option<ErrorResponse>,
+    },
+
+    /// The server failed to handle the request (5xx).
+    #[error("{}", status_message(*status, route, error))]
+    Server {
+        status: StatusCode,
+        route: String,
+        error: Option<ErrorResponse>,
+    },
+
+    /// Any other unsuccessful HTTP status.
+    #[error("{}", status_message(*status, route, error))]
+    Status {
+        status: StatusCode,
+        route: String,
+        error: Option<ErrorResponse>,
+    },
+
+    /// The request never got a response, e.g. because the connection failed or timed out.
+    #[error("Failed to send request to `{route}`")]
+    Transport {
+        route: String,
+        #[source]
+        source: reqwest::Error,
+    },
+
+    /// The response was successful but its body couldn't be parsed.
+    #[error("Failed to parse response from `{route}`")]
+    Decode {
+        route: String,
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+
+    /// The request body couldn't be serialized to JSON.
+    #[error("Failed to serialize request body to JSON")]
+    Serialize(#[from] serde_json::Error),
+
+    /// The HTTP client couldn't be built from the [`ClientConfig`](crate::ClientConfig).
+    #[error("Invalid client configuration")]
+    Config(#[source] reqwest::Error),
+
+    /// The runtime of the blocking client couldn't be started.
+    #[error("Failed to start the runtime of the blocking client")]
+    Runtime(#[source] std::io::Error),
+
+    /// The deployment didn't go live, e.g. because the new server binary failed to start.
+    /// `startup_log` holds the last log entries written since the deployment was created.
+    #[error("Deployment `{}` of app `{}` failed", deployment.id, deployment.app_slug)]
+    DeploymentFailed {
+        deployment: Box<Deployment>,
+        startup_log: Vec<LogEntry>,
+    },
+
+    /// The deployment was still pending when waiting for it timed out.
+    #[error("Deployment `{id}` didn't finish within {timeout:?}")]
+    DeploymentTimeout { id: String, timeout: Duration },
+
+    /// The server opened an upload session that can't be uploaded to.
+    #[error("Upload session `{session_id}` has a chunk size of 0")]
+    InvalidChunkSize { session_id: String },
+
+    /// A local file couldn't be read.
+    #[error("Failed to read file: {}", path.display())]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+}
+
+impl ClientError {
+    pub(crate) fn from_response(
+        status: StatusCode,
+        route: String,
+        headers: &HeaderMap,
+        body: &str,
+    ) -> Self {
+        let error = parse_error_body(body);
+
+        match status {
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Self::Unauthorized {
+                status,
+                route,
+                error,
+            },
+            StatusCode::NOT_FOUND => Self::NotFound { route, error },
+            StatusCode::CONFLICT => Self::Conflict { route, error },
+            StatusCode::TOO_MANY_REQUESTS => Self::RateLimited {
+                route,
+                retry_after: retry_after(headers),
+                error,
+            },
+            status if status.is_server_error() => Self::Server {
+                status,
+                route,
+                error,
+            },
+            status => Self::Status {
+                status,
+                route,
+                error,
+            },
+        }
+    }
+
+    /// The HTTP status of the response, if the server responded at all.
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            Self::Unauthorized { status, .. }
+            | Self::Server { status, .. }
+            | Self::Status { status, .. } => Some(*status),
+            Self::NotFound { .. } => Some(StatusCode::NOT_FOUND),
+            Self::Conflict { .. } => Some(StatusCode::CONFLICT),
+            Self::RateLimited { .. } => Some(StatusCode::TOO_MANY_REQUESTS),
+            Self::Transport { .. }
+            | Self::Decode { .. }
+            | Self::Serialize(_)
+            | Self::Config(_)
+            | Self::Runtime(_)
+            | Self::DeploymentFailed { .. }
+            | Self::DeploymentTimeout { .. }
+            | Self::InvalidChunkSize { .. }
+            | Self::Io { .. } => None,
+        }
+    }
+
+    /// The API route the failed request was sent to, like `"apps/new"`.
+    pub fn route(&self) -> Option<&str> {
+        match self {
+            Self::Unauthorized { route, .. }
+            | Self::NotFound { route, .. }
+            | Self::Conflict { route, .. }
+            | Self::RateLimited { route, .. }
+            | Self::Server { route, .. }
+            | Self::Status { route, .. }
+            | Self::Transport { route, .. }
+            | Self::Decode { route, .. } => Some(route),
+            Self::Serialize(_)
+            | Self::Config(_)
+            | Self::Runtime(_)
+            | Self::DeploymentFailed { .. }
+            | Self::DeploymentTimeout { .. }
+            | Self::InvalidChunkSize { .. }
+            | Self::Io { .. } => None,
+        }
+    }
+
+    /// The error payload sent by the server, if any.
+    pub fn server_error(&self) -> Option<&ErrorResponse> {
+        match self {
+            Self::Unauthorized { error, .. }
+            | Self::NotFound { error, .. }
+            | Self::Conflict { error, .. }
+            | Self::RateLimited { error, .. }
+            | Self::Server { error, .. }
+            | Self::Status { error, .. } => error.as_ref(),
+            _ => None,
+        }
+    }
+}
+
+fn status_message(status: StatusCode, route: &str, error: &Option<ErrorResponse>) -> String {
+    match error {
+        Some(error) => format!("Request to `{route}` failed with {status}: {error}"),
+        None => format!("Request to `{route}` failed with {status}"),
+    }
+}
+
+/// Parses the error body as [`ErrorResponse`] and falls back to using the raw text as message.
+fn parse_error_body(body: &str) -> Option<ErrorResponse> {
+    if let Ok(error) = serde_json::from_str::<ErrorResponse>(body) {
+        return Some(error);
+    }
+
+    let body = body.trim();
+    if body.is_empty() {
+        None
+    } else {
+        Some(ErrorResponse {
+            error: body.to_string(),
+            code: None,
+        })
+    }
+}
+
+/// Reads the `Retry-After` header which is either a number of seconds or an HTTP date.
+pub(crate) fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    // The date is a `std::time::SystemTime` which can't be compared to the browser's clock
+    // directly, so both are compared as durations since the epoch.
+    let date = httpdate::parse_http_date(value)
+        .ok()?
+        .duration_since(std::time::SystemTime::UNIX_EPOCH)
+        .ok()?;
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .ok()?;
+    Some(date.saturating_sub(now))
+}
@@ -1,232 +1,263 @@
-use anyhow::{Context, Result};
-use headers_core::Header;
-use log::error;
+//! Client SDK for the Oxyde Cloud API.
+//!
+//! The client also builds for `wasm32-unknown-unknown`, e.g. to call the API from a Leptos app
+//! in the browser. File uploads and the blocking client need a filesystem and threads, so they
+//! are only available on native targets behind the `upload` (default) and `blocking` features.
+
+mod apps;
+#[cfg(all(feature = "blocking", not(target_arch = "wasm32")))]
+pub mod blocking;
+mod config;
+mod deployments;
+mod domains;
+mod error;
+mod logs;
+#[cfg(all(feature = "upload", not(target_arch = "wasm32")))]
+mod progress;
+mod retry;
+mod secrets;
+mod teams;
+mod time;
+mod tokens;
+#[cfg(all(feature = "upload", not(target_arch = "wasm32")))]
+mod upload;
+
+use std::sync::Arc;
+
+use log::debug;
+use percent_encoding::{AsciiSet, NON_ALPHANUMERIC, PercentEncode, utf8_percent_encode};
 use reqwest::header::{HeaderName, HeaderValue};
 use reqwest::multipart::Form;
 use serde::{Deserialize, Serialize};
-use std::path::Path;
-use tokio::io::{AsyncReadExt, AsyncSeekExt, SeekFrom};
+
+pub use config::ClientConfig;
+pub use error::{ClientError, Result};
+pub use logs::MaybeSend;
+#[cfg(all(feature = "upload", not(target_arch = "wasm32")))]
+pub use progress::{UploadEvent, UploadProgress};
+pub use retry::RetryPolicy;
+#[cfg(all(feature = "upload", not(target_arch = "wasm32")))]
+pub use upload::{UploadOptions, sha256_file};
 
 use oxyde_cloud_common::config::CloudConfig;
 use oxyde_cloud_common::net::{
-    AppMeta, CheckAvailabilityResponse, LogRequest, LogResponse, LoginResponse, NewAppRequest,
-    NewTeamRequest, SetTeamNameRequest, SuccessResponse, Team,
+    CheckAvailabilityResponse, DeployManifest, LogEntry, LogRequest, LoginResponse,
+    ManifestResponse, NewAppRequest, NewTeamRequest, SetTeamNameRequest, SuccessResponse, Team,
+    UploadDoneResponse,
 };
 
-const BASE_URL: Option<&str> = option_env!("OXYDE_CLOUD_API_URL");
-const DEFAULT_BASE_URL: &str = "https://oxyde.cloud/api/v1/";
-const UPLOAD_CHUNK_SIZE: usize = 90 * 1024 * 1024;
-
+/// Client for the Oxyde Cloud API.
+///
+/// All methods borrow the client, and cloning it is cheap since the configuration and the
+/// connection pool are shared. So a single client can be kept in application state and used
+/// from many tasks at once.
 #[derive(Clone)]
 pub struct Client {
+    inner: Arc<ClientInner>,
+}
+
+struct ClientInner {
     client: reqwest::Client,
     api_key: String,
+    base_url: String,
+    retry_policy: RetryPolicy,
 }
 
 impl Client {
+    /// Creates a client for the Oxyde Cloud API with the default [`ClientConfig`].
     pub fn new(api_key: String) -> Self {
-        Self {
-            client: reqwest::Client::new(),
-            api_key,
-        }
+        ClientConfig::default()
+            .build(api_key)
+            .expect("default client config is valid")
     }
 
-    pub async fn teams(self) -> Result<Vec<Team>> {
-        let teams = self
-            .get("teams")
-            .send()
-            .await
-            .context("Failed to fetch teams")?;
+    /// Creates a client with [`ClientConfig::from_env`], i.e. the base URL can be overridden
+    /// with the `OXYDE_CLOUD_API_URL` environment variable.
+    pub fn from_env(api_key: String) -> Self {
+        ClientConfig::from_env()
+            .build(api_key)
+            .expect("client config from env is valid")
+    }
 
-        Ok(teams)
+    /// Starts configuring a client with a custom base URL, timeouts, proxy etc.
+    pub fn builder() -> ClientConfig {
+        ClientConfig::default()
     }
 
-    pub async fn new_app(self, app_slug: &str, team_slug: &str, name: &str) -> Result<bool> {
+    pub async fn teams(&self) -> Result<Vec<Team>> {
+        self.get("teams").send().await
+    }
+
+    pub async fn new_app(&self, app_slug: &str, team_slug: &str, name: &str) -> Result<bool> {
         let CheckAvailabilityResponse { available } = self
             .post("apps/new")
             .json(&NewAppRequest {
                 app_slug: app_slug.to_string(),
                 team_slug: team_slug.to_string(),
                 name: name.to_string(),
-            })
-            .context("Failed to serialize new app request")?
+            })?
             .send()
-            .await
-            .with_context(|| format!("Failed to check app slug availability: {app_slug}"))?;
+            .await?;
 
         Ok(available)
     }
 
-    pub async fn new_team(self, team_slug: &str) -> Result<bool> {
+    pub async fn new_team(&self, team_slug: &str) -> Result<bool> {
         let CheckAvailabilityResponse { available } = self
             .post("teams/new")
             .json(&NewTeamRequest {
                 team_slug: team_slug.to_string(),
-            })
-            .context("Failed to serialize new team request")?
+            })?
             .send()
-            .await
-            .with_context(|| format!("Failed to check team slug availability: {team_slug}"))?;
+            .await?;
 
         Ok(available)
     }
 
-    pub async fn set_team_name(self, team_slug: &str, team_name: &str) -> Result<()> {
+    pub async fn set_team_name(&self, team_slug: &str, team_name: &str) -> Result<()> {
         let _: SuccessResponse = self
             .post("teams/name")
+            .idempotent()
             .json(&SetTeamNameRequest {
                 team_slug: team_slug.to_string(),
                 team_name: team_name.to_string(),
-            })
-            .context("Failed to serialize set team name request")?
+            })?
             .send()
-            .await
-            .with_context(|| format!("Failed to set team name for team: {team_slug}"))?;
+            .await?;
 
         Ok(())
     }
 
-    pub async fn login(self) -> Result<LoginResponse> {
-        self.post("login")
-            .json(())
-            .context("Failed to serialize login request")?
+    pub async fn login(&self) -> Result<LoginResponse> {
+        self.post("login").idempotent().json(())?.send().await
+    }
+
+    /// Submits the manifest of a deployment and returns the hashes of the blobs the server is
+    /// missing. Those have to be uploaded with [`Client::upload_blob_with_options`] before
+    /// calling [`Client::upload_done`].
+    pub async fn submit_manifest(&self, manifest: &DeployManifest) -> Result<ManifestResponse> {
+        self.post("apps/manifest")
+            .idempotent()
+            .json(manifest)?
             .send()
             .await
-            .context("Failed to login with API key")
     }
 
-    pub async fn upload_file(
-        self,
-        app_slug: impl AsRef<str>,
-        path: impl AsRef<Path>,
-    ) -> Result<()> {
-        let metadata = tokio::fs::metadata(path.as_ref()).await.with_context(|| {
-            format!(
-                "Failed to read metadata for file: {}",
-                path.as_ref().display()
-            )
-        })?;
-        let total_size = metadata.len() as usize;
-        let total_chunks = total_size.div_ceil(UPLOAD_CHUNK_SIZE);
-
-        for chunk_number in 0..total_chunks {
-            let offset = chunk_number * UPLOAD_CHUNK_SIZE;
-            let len = std::cmp::min(UPLOAD_CHUNK_SIZE, total_size - offset);
-
-            let mut file = tokio::fs::File::open(path.as_ref())
-                .await
-                .with_context(|| format!("Failed to open file: {}", path.as_ref().display()))?;
-            file.seek(SeekFrom::Start(offset as u64))
-                .await
-                .context("Failed to seek in file")?;
+    /// Tells the server that all files are uploaded and starts a deployment. Returns the id of
+    /// the deployment to pass to [`Client::wait_for_deployment`].
+    pub async fn upload_done(&self, config: &CloudConfig) -> Result<String> {
+        let res: UploadDoneResponse = self.post("apps/upload-done").json(config)?.send().await?;
 
-            let mut buffer = vec![0u8; len];
-            let n = file
-                .read_exact(&mut buffer)
-                .await
-                .context("Failed to read file chunk")?;
-
-            let part = reqwest::multipart::Part::bytes(buffer[..n].to_vec())
-                .file_name(path.as_ref().to_string_lossy().to_string());
-
-            let form = reqwest::multipart::Form::new()
-                .part("file", part)
-                .text("chunk_number", chunk_number.to_string())
-                .text("total_chunks", total_chunks.to_string());
-
-            let _: SuccessResponse = self
-                .clone()
-                .post("apps/upload-file")
-                .multipart(form)
-                .header(
-                    AppMeta::name(),
-                    AppMeta {
-                        app_slug: app_slug.as_ref().to_string(),
-                    }
-                    .to_string_value(),
-                )
-                .send()
-                .await
-                .with_context(|| {
-                    format!(
-                        "Failed to upload file chunk {}/{}",
-                        chunk_number + 1,
-                        total_chunks
-                    )
-                })?;
-        }
+        Ok(res.deployment_id)
+    }
 
-        Ok(())
+    /// Fetches the entries of a log that match the filters of `request`. Use
+    /// [`Client::log_stream`] to follow a log instead.
+    pub async fn log(&self, request: &LogRequest) -> Result<Vec<LogEntry>> {
+        self.post("log").idempotent().json(request)?.send().await
     }
 
-    pub async fn upload_done(self, config: &CloudConfig) -> Result<()> {
-        let _: SuccessResponse = self
-            .post("apps/upload-done")
-            .json(config)
-            .context("Failed to serialize upload done request")?
-            .send()
-            .await
-            .context("Failed to signal upload completion")?;
+    pub fn post(&self, route: &str) -> ClientBuilder {
+        let url = self.build_route(route);
 
-        Ok(())
+        ClientBuilder::new(
+            route,
+            self.inner.client.post(url),
+            self.inner.retry_policy.clone(),
+            false,
+        )
+        .auth_header(&self.inner.api_key)
     }
 
-    pub async fn log(self, name: &str) -> Result<String> {
-        let res: LogResponse = self
-            .post("log")
-            .json(&LogRequest {
-                name: name.to_string(),
-            })
-            .context("Failed to serialize log request")?
-            .send()
-            .await
-            .with_context(|| format!("Failed to fetch logs for app: {name}"))?;
+    pub fn get(&self, route: &str) -> ClientBuilder {
+        let url = self.build_route(route);
 
-        Ok(res.log)
+        ClientBuilder::new(
+            route,
+            self.inner.client.get(url),
+            self.inner.retry_policy.clone(),
+            true,
+        )
+        .auth_header(&self.inner.api_key)
     }
 
-    pub fn post(self, route: &str) -> ClientBuilder {
-        let url = Self::build_route(route);
-
-        ClientBuilder(self.client.post(url)).auth_header(&self.api_key)
+    fn build_route(&self, route: &str) -> String {
+        format!("{}{route}", self.inner.base_url)
     }
+}
+
+/// Everything but the characters that are allowed unescaped in a URL path segment.
+const PATH_SEGMENT: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+/// Escapes a value that is interpolated into a route like `apps/{app_slug}/deployments`, so it
+/// stays a single path segment whatever characters it contains.
+pub(crate) fn path_segment(value: &str) -> PercentEncode<'_> {
+    utf8_percent_encode(value, PATH_SEGMENT)
+}
+
+pub struct ClientBuilder {
+    route: String,
+    request: reqwest::RequestBuilder,
+    retry_policy: RetryPolicy,
+    idempotent: bool,
+}
 
-    pub fn get(self, route: &str) -> ClientBuilder {
-        let url = Self::build_route(route);
-        println!("GET request URL: {}", url);
+impl ClientBuilder {
+    fn new(
+        route: &str,
+        request: reqwest::RequestBuilder,
+        retry_policy: RetryPolicy,
+        idempotent: bool,
+    ) -> Self {
+        Self {
+            route: route.to_string(),
+            request,
+            retry_policy,
+            idempotent,
+        }
+    }
 
-        ClientBuilder(self.client.get(url)).auth_header(&self.api_key)
+    fn map(self, f: impl FnOnce(reqwest::RequestBuilder) -> reqwest::RequestBuilder) -> Self {
+        Self {
+            request: f(self.request),
+            ..self
+        }
     }
 
-    fn build_route(route: &str) -> String {
-        let base_url = std::env::var("OXYDE_CLOUD_API_URL")
-            .unwrap_or(BASE_URL.unwrap_or(DEFAULT_BASE_URL).to_string());
-        format!("{base_url}{route}")
+    /// Marks the request as safe to be sent more than once so it's retried according to the
+    /// client's [`RetryPolicy`].
+    pub fn idempotent(mut self) -> Self {
+        self.idempotent = true;
+        self
     }
-}
 
-pub struct ClientBuilder(reqwest::RequestBuilder);
+    /// Sends an `Idempotency-Key` header which lets the server deduplicate the request. This
+    /// makes it safe to retry otherwise non-idempotent requests like creating an app.
+    pub fn idempotency_key(self, key: impl AsRef<str>) -> Self {
+        self.map(|r| r.header("Idempotency-Key", key.as_ref()))
+            .idempotent()
+    }
 
-impl ClientBuilder {
     pub fn auth_header(self, api_key: &str) -> Self {
-        Self(self.0.header("Authorization", format!("Bearer {api_key}")))
+        self.map(|r| r.header("Authorization", format!("Bearer {api_key}")))
     }
 
     pub fn body<T: Into<reqwest::Body>>(self, body: T) -> Self {
-        Self(self.0.body(body))
+        self.map(|r| r.body(body))
     }
 
     pub fn multipart(self, form: Form) -> Self {
-        Self(self.0.multipart(form))
+        self.map(|r| r.multipart(form))
     }
 
     pub fn json<Body: Serialize>(self, json: Body) -> Result<ClientBuilder> {
-        let json =
-            serde_json::to_string(&json).context("Failed to serialize request body to JSON")?;
+        let json = serde_json::to_string(&json)?;
 
-        Ok(Self(
-            self.0.header("Content-Type", "application/json").body(json),
-        ))
+        Ok(self.map(|r| r.header("Content-Type", "application/json").body(json)))
     }
 
     pub fn header<K, V>(self, key: K, value: V) -> Self
@@ -236,29 +267,71 @@ impl ClientBuilder {
         HeaderValue: TryFrom<V>,
         <HeaderValue as TryFrom<V>>::Error: Into<http::Error>,
     {
-        Self(self.0.header(key, value))
+        self.map(|r| r.header(key, value))
     }
 
     pub async fn send<Resp>(self) -> Result<Resp>
     where
         for<'de> Resp: Deserialize<'de>,
     {
-        let res = self.0.send().await.context("Failed to send HTTP request")?;
-
-        match res.error_for_status_ref() {
-            Ok(_) => res
-                .json::<Resp>()
+        let Self {
+            route,
+            request,
+            retry_policy,
+            idempotent,
+        } = self;
+
+        // Streaming bodies like multipart forms can't be cloned and thus can't be retried.
+        if idempotent && request.try_clone().is_some() {
+            retry_policy
+                .run(|| {
+                    let request = request.try_clone().expect("checked above");
+                    Self::send_once(route.clone(), request)
+                })
                 .await
-                .context("Failed to parse response JSON"),
-            Err(err) => {
-                let err_text = res
-                    .text()
-                    .await
-                    .unwrap_or_else(|_| "Failed to read error response".to_string());
-                error!("Received error:\n{err_text:#?}");
-
-                Err(err).context("HTTP request failed")
-            }
+        } else {
+            Self::send_once(route, request).await
+        }
+    }
+
+    /// Sends the request once without retrying and returns the response as is, e.g. to stream
+    /// its body. Unsuccessful responses are turned into errors.
+    pub(crate) async fn send_raw(self) -> Result<reqwest::Response> {
+        Self::response(self.route, self.request).await
+    }
+
+    async fn send_once<Resp>(route: String, request: reqwest::RequestBuilder) -> Result<Resp>
+    where
+        for<'de> Resp: Deserialize<'de>,
+    {
+        let res = Self::response(route.clone(), request).await?;
+
+        res.json::<Resp>()
+            .await
+            .map_err(|source| ClientError::Decode {
+                route,
+                source: source.into(),
+            })
+    }
+
+    async fn response(
+        route: String,
+        request: reqwest::RequestBuilder,
+    ) -> Result<reqwest::Response> {
+        let res = match request.send().await {
+            Ok(res) => res,
+            Err(source) => return Err(ClientError::Transport { route, source }),
+        };
+
+        let status = res.status();
+        if status.is_success() {
+            return Ok(res);
         }
+
+        let headers = res.headers().clone();
+        let body = res.text().await.unwrap_or_default();
+        debug!("Received error from `{route}` ({status}):\n{body}");
+
+        Err(ClientError::from_response(status, route, &headers, &body))
     }
 }
@@ -0,0 +1,186 @@
+use std::collections::VecDeque;
+
+use bytes::Bytes;
+use futures_util::{Stream, StreamExt, stream};
+use log::{debug, warn};
+use oxyde_cloud_common::net::{LogEntry, LogStreamRequest};
+
+use crate::{Client, ClientError, Result, time};
+
+const LOG_STREAM_ROUTE: &str = "log/stream";
+
+#[cfg(not(target_arch = "wasm32"))]
+type Body = stream::BoxStream<'static, reqwest::Result<Bytes>>;
+#[cfg(target_arch = "wasm32")]
+type Body = stream::LocalBoxStream<'static, reqwest::Result<Bytes>>;
+
+/// `Send` on native targets. In the browser responses can't be sent to other threads, so the
+/// bound is dropped there.
+#[cfg(not(target_arch = "wasm32"))]
+pub trait MaybeSend: Send {}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl<T: Send> MaybeSend for T {}
+
+/// `Send` on native targets. In the browser responses can't be sent to other threads, so the
+/// bound is dropped there.
+#[cfg(target_arch = "wasm32")]
+pub trait MaybeSend {}
+
+#[cfg(target_arch = "wasm32")]
+impl<T> MaybeSend for T {}
+
+impl Client {
+    /// Follows a log. The stream yields the matching entries already written and then every new
+    /// one as it arrives. It only ends with an error.
+    ///
+    /// Dropped connections are re-established according to the client's
+    /// [`RetryPolicy`](crate::RetryPolicy) and resume right after the last entry that was
+    /// received, so no entry is skipped or repeated. To resume after a restart, pass the
+    /// [`LogEntry::cursor`] of the last entry seen as [`LogStreamRequest::cursor`].
+    ///
+    /// ```no_run
+    /// # async fn example(client: oxyde_cloud_client::Client) -> oxyde_cloud_client::Result<()> {
+    /// use futures_util::StreamExt;
+    /// use oxyde_cloud_common::net::LogStreamRequest;
+    ///
+    /// let mut entries = std::pin::pin!(client.log_stream(&LogStreamRequest::new("my-app")));
+    /// while let Some(entry) = entries.next().await {
+    ///     println!("{}", entry?.message);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn log_stream(
+        &self,
+        request: &LogStreamRequest,
+    ) -> impl Stream<Item = Result<LogEntry>> + MaybeSend + 'static {
+        let state = LogStream {
+            client: self.clone(),
+            request: request.clone(),
+            response: None,
+            buffer: Vec::new(),
+            entries: VecDeque::new(),
+            failures: 0,
+        };
+
+        // After an error the state is gone and the stream ends.
+        stream::unfold(Some(state), |state| async move {
+            let mut state = state?;
+
+            match state.next_entry().await {
+                Ok(entry) => Some((Ok(entry), Some(state))),
+                Err(err) => Some((Err(err), None)),
+            }
+        })
+    }
+}
+
+struct LogStream {
+    client: Client,
+    /// Sent on every connection attempt with the cursor of the last entry received.
+    request: LogStreamRequest,
+    response: Option<Body>,
+    /// Bytes of an entry that hasn't been received completely yet.
+    buffer: Vec<u8>,
+    entries: VecDeque<LogEntry>,
+    failures: u32,
+}
+
+impl LogStream {
+    async fn next_entry(&mut self) -> Result<LogEntry> {
+        loop {
+            if let Some(entry) = self.entries.pop_front() {
+                if entry.cursor.is_some() {
+                    self.request.cursor = entry.cursor.clone();
+                }
+                return Ok(entry);
+            }
+
+            let Some(response) = &mut self.response else {
+                match self.connect().await {
+                    Ok(response) => {
+                        self.failures = 0;
+                        self.response = Some(Box::pin(response.bytes_stream()));
+                    }
+                    Err(err) => self.reconnect_after(err).await?,
+                }
+                continue;
+            };
+
+            match response.next().await {
+                Some(Ok(bytes)) => {
+                    self.buffer.extend_from_slice(&bytes);
+                    self.split_entries()?;
+                }
+                None => {
+                    // The server ends streams from time to time. Pick up after the last entry.
+                    debug!("Log stream of {} ended. Reconnecting.", self.request.name);
+                    self.disconnect();
+                    time::sleep(self.client.inner.retry_policy.initial_backoff).await;
+                }
+                Some(Err(source)) => {
+                    self.disconnect();
+                    self.reconnect_after(ClientError::Transport {
+                        route: LOG_STREAM_ROUTE.to_string(),
+                        source,
+                    })
+                    .await?;
+                }
+            }
+        }
+    }
+
+    /// The request is built up front, so the returned future doesn't borrow `self`, which isn't
+    /// `Sync` because of the response body.
+    fn connect(&self) -> impl Future<Output = Result<reqwest::Response>> + 'static {
+        let request = self.client.post(LOG_STREAM_ROUTE).json(&self.request);
+
+        async move { request?.send_raw().await }
+    }
+
+    /// Waits before the next connection attempt or returns `err` if it isn't retryable or the
+    /// retries are used up.
+    async fn reconnect_after(&mut self, err: ClientError) -> Result<()> {
+        let retry_policy = &self.client.inner.retry_policy;
+
+        if self.failures >= retry_policy.max_retries {
+            return Err(err);
+        }
+        let Some(delay) = retry_policy.delay(&err, self.failures) else {
+            return Err(err);
+        };
+
+        self.failures += 1;
+        warn!("{err}. Reconnecting in {delay:?}");
+        time::sleep(delay).await;
+
+        Ok(())
+    }
+
+    /// Drops the connection together with a partially received entry. It is sent again after
+    /// reconnecting since the cursor hasn't moved past it.
+    fn disconnect(&mut self) {
+        self.response = None;
+        self.buffer.clear();
+    }
+
+    fn split_entries(&mut self) -> Result<()> {
+        while let Some(end) = self.buffer.iter().position(|&b| b == b'\n') {
+            let line = self.buffer.drain(..=end).collect::<Vec<_>>();
+
+            // Empty lines are sent as keep-alive.
+            if line.trim_ascii().is_empty() {
+                continue;
+            }
+
+            let entry = serde_json::from_slice(&line).map_err(|source| ClientError::Decode {
+                route: LOG_STREAM_ROUTE.to_string(),
+                source: source.into(),
+            })?;
+            self.entries.push_back(entry);
+        }
+
+        Ok(())
+    }
+}
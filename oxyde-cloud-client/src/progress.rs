@@ -0,0 +1,186 @@
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::task::{Context, Poll};
+
+use bytes::Bytes;
+use futures_util::Stream;
+
+/// Something that happened during a single upload.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UploadEvent {
+    /// The upload started. When resuming an upload, `bytes_sent` already counts the chunks
+    /// the server had received before.
+    Started {
+        file_name: String,
+        bytes_sent: u64,
+        total_bytes: u64,
+        chunk_count: u64,
+    },
+
+    /// More bytes have been streamed to the server. `bytes_sent` can go down again if a chunk
+    /// failed and is retried.
+    Progress {
+        file_name: String,
+        bytes_sent: u64,
+        total_bytes: u64,
+    },
+
+    /// The server accepted a chunk.
+    ChunkUploaded {
+        file_name: String,
+        chunk_index: u64,
+        chunk_count: u64,
+    },
+
+    /// All chunks have been uploaded.
+    Finished { file_name: String, total_bytes: u64 },
+}
+
+/// Receives [`UploadEvent`]s. Set it on [`UploadOptions::progress`](crate::UploadOptions).
+///
+/// Implemented for closures, so a progress bar can be driven directly or the events can be
+/// forwarded into a channel:
+///
+/// ```
+/// # use std::sync::Arc;
+/// # use oxyde_cloud_client::{UploadEvent, UploadOptions};
+/// let (tx, _rx) = std::sync::mpsc::channel::<UploadEvent>();
+/// let tx = std::sync::Mutex::new(tx);
+///
+/// let options = UploadOptions {
+///     progress: Some(Arc::new(move |event: UploadEvent| {
+///         let _ = tx.lock().unwrap().send(event);
+///     })),
+///     ..Default::default()
+/// };
+/// ```
+pub trait UploadProgress: Send + Sync {
+    fn on_event(&self, event: UploadEvent);
+}
+
+impl<F> UploadProgress for F
+where
+    F: Fn(UploadEvent) + Send + Sync,
+{
+    fn on_event(&self, event: UploadEvent) {
+        self(event)
+    }
+}
+
+/// Tracks the bytes sent for one upload and turns them into [`UploadEvent::Progress`] events.
+#[derive(Clone)]
+pub(crate) struct ProgressTracker {
+    file_name: String,
+    total_bytes: u64,
+    bytes_sent: Arc<AtomicU64>,
+    progress: Option<Arc<dyn UploadProgress>>,
+}
+
+impl ProgressTracker {
+    pub fn new(
+        file_name: &str,
+        total_bytes: u64,
+        progress: Option<Arc<dyn UploadProgress>>,
+    ) -> Self {
+        Self {
+            file_name: file_name.to_string(),
+            total_bytes,
+            bytes_sent: Arc::new(AtomicU64::new(0)),
+            progress,
+        }
+    }
+
+    pub fn started(&self, bytes_sent: u64, chunk_count: u64) {
+        self.bytes_sent.store(bytes_sent, Ordering::Relaxed);
+
+        self.emit(|file_name| UploadEvent::Started {
+            file_name,
+            bytes_sent,
+            total_bytes: self.total_bytes,
+            chunk_count,
+        });
+    }
+
+    pub fn chunk_uploaded(&self, chunk_index: u64, chunk_count: u64) {
+        self.emit(|file_name| UploadEvent::ChunkUploaded {
+            file_name,
+            chunk_index,
+            chunk_count,
+        });
+    }
+
+    pub fn finished(&self) {
+        self.emit(|file_name| UploadEvent::Finished {
+            file_name,
+            total_bytes: self.total_bytes,
+        });
+    }
+
+    /// Wraps a body stream so every piece that is polled from it counts as sent. The returned
+    /// counter holds the bytes of this attempt only, so they can be taken back with
+    /// [`ProgressTracker::rewind`] if the attempt fails.
+    pub fn track<S>(&self, stream: S) -> (TrackedStream<S>, Arc<AtomicU64>) {
+        let attempt_sent = Arc::new(AtomicU64::new(0));
+
+        let stream = TrackedStream {
+            inner: stream,
+            tracker: self.clone(),
+            attempt_sent: Arc::clone(&attempt_sent),
+        };
+
+        (stream, attempt_sent)
+    }
+
+    pub fn rewind(&self, attempt_sent: &AtomicU64) {
+        let bytes = attempt_sent.swap(0, Ordering::Relaxed);
+        if bytes > 0 {
+            let bytes_sent = self.bytes_sent.fetch_sub(bytes, Ordering::Relaxed) - bytes;
+            self.emit_progress(bytes_sent);
+        }
+    }
+
+    fn add(&self, bytes: u64) {
+        let bytes_sent = self.bytes_sent.fetch_add(bytes, Ordering::Relaxed) + bytes;
+        self.emit_progress(bytes_sent);
+    }
+
+    fn emit_progress(&self, bytes_sent: u64) {
+        self.emit(|file_name| UploadEvent::Progress {
+            file_name,
+            bytes_sent,
+            total_bytes: self.total_bytes,
+        });
+    }
+
+    fn emit(&self, event: impl FnOnce(String) -> UploadEvent) {
+        if let Some(progress) = &self.progress {
+            progress.on_event(event(self.file_name.clone()));
+        }
+    }
+}
+
+pub(crate) struct TrackedStream<S> {
+    inner: S,
+    tracker: ProgressTracker,
+    attempt_sent: Arc<AtomicU64>,
+}
+
+impl<S, E> Stream for TrackedStream<S>
+where
+    S: Stream<Item = Result<Bytes, E>> + Unpin,
+{
+    type Item = Result<Bytes, E>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let poll = Pin::new(&mut self.inner).poll_next(cx);
+
+        if let Poll::Ready(Some(Ok(bytes))) = &poll {
+            let len = bytes.len() as u64;
+            self.attempt_sent.fetch_add(len, Ordering::Relaxed);
+            self.tracker.add(len);
+        }
+
+        poll
+    }
+}
@@ -0,0 +1,122 @@
+use std::future::Future;
+use std::time::Duration;
+
+use log::warn;
+use reqwest::StatusCode;
+
+use crate::{ClientError, Result, time};
+
+/// Controls how often and how fast failed requests are retried.
+///
+/// Requests are retried on connection errors, on `502 Bad Gateway`, `503 Service Unavailable`
+/// and `504 Gateway Timeout` using exponential backoff with jitter, as well as on
+/// `429 Too Many Requests` where the server's `Retry-After` header is honored.
+///
+/// Only idempotent requests are retried. `GET` requests are idempotent by default, `POST`
+/// requests have to opt in with [`ClientBuilder::idempotent`](crate::ClientBuilder::idempotent)
+/// or [`ClientBuilder::idempotency_key`](crate::ClientBuilder::idempotency_key).
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// How many times a request is retried after the first attempt failed.
+    pub max_retries: u32,
+
+    /// Backoff before the first retry. It doubles with every further retry.
+    pub initial_backoff: Duration,
+
+    /// Upper bound for the exponential backoff.
+    pub max_backoff: Duration,
+
+    /// Upper bound for how long a `Retry-After` header is honored. If the server asks to wait
+    /// longer the error is returned instead.
+    pub max_retry_after: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 4,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            max_retry_after: Duration::from_secs(120),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy that never retries.
+    pub fn none() -> Self {
+        Self {
+            max_retries: 0,
+            ..Default::default()
+        }
+    }
+
+    /// Runs `op` until it succeeds, fails with an error that isn't retryable or the retries are
+    /// used up.
+    pub(crate) async fn run<T, F, Fut>(&self, mut op: F) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut retry = 0;
+
+        loop {
+            let err = match op().await {
+                Ok(value) => return Ok(value),
+                Err(err) => err,
+            };
+
+            if retry >= self.max_retries {
+                return Err(err);
+            }
+
+            let Some(delay) = self.delay(&err, retry) else {
+                return Err(err);
+            };
+
+            retry += 1;
+            warn!(
+                "{err}. Retrying in {delay:?} (attempt {}/{})",
+                retry + 1,
+                self.max_retries + 1
+            );
+            time::sleep(delay).await;
+        }
+    }
+
+    /// Returns how long to wait before retrying after `err` or `None` if it isn't retryable.
+    pub(crate) fn delay(&self, err: &ClientError, retry: u32) -> Option<Duration> {
+        match err {
+            ClientError::Transport { .. } => Some(self.backoff(retry)),
+            ClientError::Server { status, .. }
+                if matches!(
+                    *status,
+                    StatusCode::BAD_GATEWAY
+                        | StatusCode::SERVICE_UNAVAILABLE
+                        | StatusCode::GATEWAY_TIMEOUT
+                ) =>
+            {
+                Some(self.backoff(retry))
+            }
+            ClientError::RateLimited {
+                retry_after: Some(retry_after),
+                ..
+            } => (*retry_after <= self.max_retry_after).then_some(*retry_after),
+            ClientError::RateLimited {
+                retry_after: None, ..
+            } => Some(self.backoff(retry)),
+            _ => None,
+        }
+    }
+
+    /// Exponential backoff with "equal jitter": somewhere between half and the full delay.
+    fn backoff(&self, retry: u32) -> Duration {
+        let exponential = self
+            .initial_backoff
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_backoff);
+
+        let half = exponential / 2;
+        half + half.mul_f64(fastrand::f64())
+    }
+}
@@ -0,0 +1,66 @@
+use oxyde_cloud_common::net::{
+    CheckSecretsRequest, CheckSecretsResponse, DeleteSecretRequest, Secret, SecretScope,
+    SecretsRequest, SetSecretRequest, SuccessResponse,
+};
+
+use crate::{Client, Result};
+
+impl Client {
+    /// Creates the secret `name` in `scope` or replaces its value. Apps can reference it in the
+    /// `[env]` section of their config as `${{ secrets.NAME }}`.
+    pub async fn set_secret(&self, scope: &SecretScope, name: &str, value: &str) -> Result<()> {
+        let _: SuccessResponse = self
+            .post("secrets/set")
+            .idempotent()
+            .json(&SetSecretRequest {
+                scope: scope.clone(),
+                name: name.to_string(),
+                value: value.to_string(),
+            })?
+            .send()
+            .await?;
+
+        Ok(())
+    }
+
+    /// Lists the names of the secrets in `scope` and when they were last set. The values can't
+    /// be read back.
+    pub async fn list_secrets(&self, scope: &SecretScope) -> Result<Vec<Secret>> {
+        self.post("secrets/list")
+            .idempotent()
+            .json(&SecretsRequest {
+                scope: scope.clone(),
+            })?
+            .send()
+            .await
+    }
+
+    pub async fn delete_secret(&self, scope: &SecretScope, name: &str) -> Result<()> {
+        let _: SuccessResponse = self
+            .post("secrets/delete")
+            .json(&DeleteSecretRequest {
+                scope: scope.clone(),
+                name: name.to_string(),
+            })?
+            .send()
+            .await?;
+
+        Ok(())
+    }
+
+    /// Returns which of the secrets `names` the app can't access, i.e. that are neither
+    /// secrets of the app nor of its team. Deploys check this to fail before anything is built.
+    pub async fn missing_secrets(&self, app_slug: &str, names: &[String]) -> Result<Vec<String>> {
+        let res: CheckSecretsResponse = self
+            .post("secrets/check")
+            .idempotent()
+            .json(&CheckSecretsRequest {
+                app_slug: app_slug.to_string(),
+                names: names.to_vec(),
+            })?
+            .send()
+            .await?;
+
+        Ok(res.missing)
+    }
+}
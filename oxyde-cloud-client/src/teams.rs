@@ -0,0 +1,76 @@
+use oxyde_cloud_common::net::{
+    InviteMemberRequest, RemoveMemberRequest, SetMemberRoleRequest, SuccessResponse,
+    TeamInvitation, TeamMember, TeamRole,
+};
+
+use crate::{Client, Result, path_segment};
+
+impl Client {
+    pub async fn team_members(&self, team_slug: &str) -> Result<Vec<TeamMember>> {
+        self.get(&format!("teams/{}/members", path_segment(team_slug)))
+            .send()
+            .await
+    }
+
+    /// Lists the invitations of a team that haven't been accepted yet.
+    pub async fn team_invitations(&self, team_slug: &str) -> Result<Vec<TeamInvitation>> {
+        self.get(&format!("teams/{}/invitations", path_segment(team_slug)))
+            .send()
+            .await
+    }
+
+    /// Invites `email` to join a team with `role`. They become a member once they accept the
+    /// invitation sent to them.
+    pub async fn invite_member(
+        &self,
+        team_slug: &str,
+        email: &str,
+        role: TeamRole,
+    ) -> Result<TeamInvitation> {
+        self.post("teams/invite")
+            .json(&InviteMemberRequest {
+                team_slug: team_slug.to_string(),
+                email: email.to_string(),
+                role,
+            })?
+            .send()
+            .await
+    }
+
+    /// Removes a member from a team. A team always keeps at least one owner, so removing the
+    /// last one fails with [`ClientError::Conflict`](crate::ClientError::Conflict).
+    pub async fn remove_member(&self, team_slug: &str, username: &str) -> Result<()> {
+        let _: SuccessResponse = self
+            .post("teams/remove-member")
+            .json(&RemoveMemberRequest {
+                team_slug: team_slug.to_string(),
+                username: username.to_string(),
+            })?
+            .send()
+            .await?;
+
+        Ok(())
+    }
+
+    /// Changes the role of a member. Like [`Client::remove_member`] this fails if it would
+    /// leave the team without an owner.
+    pub async fn set_member_role(
+        &self,
+        team_slug: &str,
+        username: &str,
+        role: TeamRole,
+    ) -> Result<()> {
+        let _: SuccessResponse = self
+            .post("teams/role")
+            .idempotent()
+            .json(&SetMemberRoleRequest {
+                team_slug: team_slug.to_string(),
+                username: username.to_string(),
+                role,
+            })?
+            .send()
+            .await?;
+
+        Ok(())
+    }
+}
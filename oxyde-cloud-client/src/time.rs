@@ -0,0 +1,15 @@
+//! Clocks and timers that work on native targets as well as in the browser, where `std::time`
+//! panics and there is no Tokio runtime.
+
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) use std::time::SystemTime;
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) use tokio::time::{Instant, sleep};
+
+#[cfg(target_arch = "wasm32")]
+pub(crate) use web_time::{Instant, SystemTime};
+
+#[cfg(target_arch = "wasm32")]
+pub(crate) async fn sleep(duration: std::time::Duration) {
+    gloo_timers::future::sleep(duration).await;
+}
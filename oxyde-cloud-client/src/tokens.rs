@@ -0,0 +1,31 @@
+use oxyde_cloud_common::net::{
+    ApiToken, NewTokenRequest, NewTokenResponse, RevokeTokenRequest, SuccessResponse,
+};
+
+use crate::{Client, Result};
+
+impl Client {
+    /// Creates an API token with the scopes, restriction and expiry of `request`. The returned
+    /// [`NewTokenResponse::secret`] is the only time the token can be read, so store it right
+    /// away, e.g. as a CI secret.
+    pub async fn create_token(&self, request: &NewTokenRequest) -> Result<NewTokenResponse> {
+        self.post("tokens/new").json(request)?.send().await
+    }
+
+    /// Lists the API tokens of the user that haven't been revoked.
+    pub async fn list_tokens(&self) -> Result<Vec<ApiToken>> {
+        self.get("tokens").send().await
+    }
+
+    /// Revokes an API token. Requests authenticated with it fail right away.
+    pub async fn revoke_token(&self, id: &str) -> Result<()> {
+        let _: SuccessResponse = self
+            .post("tokens/revoke")
+            .idempotent()
+            .json(&RevokeTokenRequest { id: id.to_string() })?
+            .send()
+            .await?;
+
+        Ok(())
+    }
+}
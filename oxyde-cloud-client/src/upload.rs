@@ -0,0 +1,699 @@
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::UNIX_EPOCH;
+
+use futures_util::{StreamExt, TryStreamExt, stream};
+use headers_core::Header;
+use log::{debug, info};
+use oxyde_cloud_common::net::{
+    AppMeta, NewBlobRequest, NewUploadSessionRequest, SuccessResponse, UploadSession,
+    UploadSessionRequest,
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeekExt, SeekFrom};
+use tokio::sync::Mutex;
+use tokio_util::io::ReaderStream;
+
+use crate::progress::ProgressTracker;
+use crate::{Client, ClientError, Result, UploadProgress};
+
+const UPLOAD_CHUNK_SIZE: u64 = 90 * 1024 * 1024;
+const STREAM_BUFFER_SIZE: usize = 64 * 1024;
+
+/// Serializes access to upload state files so concurrent uploads don't overwrite each
+/// other's entries.
+static STATE_FILE_LOCK: Mutex<()> = Mutex::const_new(());
+
+/// Options for [`Client::upload_file_with_options`] and the other `*_with_options` uploads.
+#[derive(Clone)]
+pub struct UploadOptions {
+    /// Path of a small JSON file that remembers open upload sessions. If an upload is
+    /// interrupted, the next upload of the same unchanged file picks up the session from there
+    /// and only sends the chunks the server doesn't have yet.
+    pub state_file: Option<PathBuf>,
+
+    /// How many chunks of the same file are uploaded at the same time.
+    pub chunk_concurrency: usize,
+
+    /// Receives an [`UploadEvent`](crate::UploadEvent) whenever the upload makes progress.
+    pub progress: Option<Arc<dyn UploadProgress>>,
+}
+
+impl Default for UploadOptions {
+    fn default() -> Self {
+        Self {
+            state_file: None,
+            chunk_concurrency: 2,
+            progress: None,
+        }
+    }
+}
+
+impl std::fmt::Debug for UploadOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UploadOptions")
+            .field("state_file", &self.state_file)
+            .field("chunk_concurrency", &self.chunk_concurrency)
+            .field("progress", &self.progress.is_some())
+            .finish()
+    }
+}
+
+impl UploadOptions {
+    pub fn with_state_file(state_file: impl Into<PathBuf>) -> Self {
+        Self {
+            state_file: Some(state_file.into()),
+            ..Default::default()
+        }
+    }
+}
+
+impl Client {
+    /// Uploads a file in chunks. Equivalent to [`Client::upload_file_with_options`] with default
+    /// options, so interrupted uploads can't be resumed.
+    pub async fn upload_file(
+        &self,
+        app_slug: impl AsRef<str>,
+        path: impl AsRef<Path>,
+    ) -> Result<()> {
+        self.upload_file_with_options(app_slug, path, &UploadOptions::default())
+            .await
+    }
+
+    /// Uploads a file in chunks within an upload session.
+    ///
+    /// The session is opened with the size and hash of the file. If `options` contain a state
+    /// file with a session for the same unchanged file, that session is resumed instead and
+    /// only the chunks the server is missing are uploaded.
+    pub async fn upload_file_with_options(
+        &self,
+        app_slug: impl AsRef<str>,
+        path: impl AsRef<Path>,
+        options: &UploadOptions,
+    ) -> Result<()> {
+        self.upload_in_session(
+            app_slug.as_ref(),
+            path.as_ref(),
+            UploadTarget::File,
+            options,
+        )
+        .await
+    }
+
+    /// Uploads the contents of a file as a content-addressed blob, usually one that was reported
+    /// missing by [`Client::submit_manifest`](crate::Client::submit_manifest).
+    ///
+    /// `sha256` has to be the hex encoded hash of the file, e.g. as computed by
+    /// [`sha256_file`](crate::sha256_file). Uploads are resumable like with
+    /// [`Client::upload_file_with_options`].
+    pub async fn upload_blob_with_options(
+        &self,
+        app_slug: impl AsRef<str>,
+        path: impl AsRef<Path>,
+        sha256: &str,
+        options: &UploadOptions,
+    ) -> Result<()> {
+        self.upload_in_session(
+            app_slug.as_ref(),
+            path.as_ref(),
+            UploadTarget::Blob { sha256 },
+            options,
+        )
+        .await
+    }
+
+    async fn upload_in_session(
+        &self,
+        app_slug: &str,
+        path: &Path,
+        target: UploadTarget<'_>,
+        options: &UploadOptions,
+    ) -> Result<()> {
+        let file_name = path.to_string_lossy().to_string();
+
+        let fingerprint = FileFingerprint::read(path).await?;
+        let state_key = match target {
+            UploadTarget::File => format!("{app_slug}:{file_name}"),
+            UploadTarget::Blob { sha256 } => format!("{app_slug}:blob:{sha256}"),
+        };
+
+        let resumable = match &options.state_file {
+            Some(state_file) => UploadState::load(state_file)
+                .await
+                .sessions
+                .remove(&state_key)
+                .filter(|entry| entry.matches(&fingerprint)),
+            None => None,
+        };
+
+        let mut session = None;
+
+        if let Some(entry) = &resumable {
+            match self.upload_session(&entry.session_id).await {
+                Ok(existing) => {
+                    info!(
+                        "Resuming upload of {file_name} ({} chunks already uploaded)",
+                        existing.received_chunks.len()
+                    );
+                    session = Some((existing, entry.sha256.clone()));
+                }
+                Err(ClientError::NotFound { .. }) => {
+                    debug!("Upload session for {file_name} expired. Starting over.");
+                }
+                Err(err) => return Err(err),
+            }
+        }
+
+        let (session, sha256) = match (session, target) {
+            (Some(session), _) => session,
+            (None, UploadTarget::File) => {
+                let sha256 = sha256_file(path).await?;
+                let session = self
+                    .open_session(
+                        app_slug,
+                        &file_name,
+                        fingerprint.size,
+                        target,
+                        Some(sha256.clone()),
+                    )
+                    .await?;
+
+                (session, sha256)
+            }
+            (None, UploadTarget::Blob { sha256 }) => {
+                let session = self
+                    .open_session(app_slug, &file_name, fingerprint.size, target, None)
+                    .await?;
+
+                (session, sha256.to_string())
+            }
+        };
+
+        if let Some(state_file) = &options.state_file {
+            UploadState::update(state_file, |state| {
+                state.sessions.insert(
+                    state_key.clone(),
+                    StateEntry {
+                        session_id: session.session_id.clone(),
+                        sha256,
+                        size: fingerprint.size,
+                        modified: fingerprint.modified,
+                    },
+                );
+            })
+            .await?;
+        }
+
+        let received = session
+            .received_chunks
+            .iter()
+            .copied()
+            .collect::<BTreeSet<_>>();
+        let total_chunks = chunk_count(fingerprint.size, session.chunk_size);
+
+        let tracker = ProgressTracker::new(&file_name, fingerprint.size, options.progress.clone());
+        let already_sent = received
+            .iter()
+            .filter(|&&number| number < total_chunks)
+            .map(|&number| Chunk::new(&session, number, fingerprint.size).len)
+            .sum();
+        tracker.started(already_sent, total_chunks);
+
+        // The first failing chunk drops all other in-flight chunk uploads. They're picked up
+        // again when the upload is resumed.
+        stream::iter((0..total_chunks).filter(|n| !received.contains(n)))
+            .map(Ok::<_, ClientError>)
+            .try_for_each_concurrent(options.chunk_concurrency.max(1), |number| {
+                let chunk = Chunk::new(&session, number, fingerprint.size);
+                let client = self;
+                let tracker = &tracker;
+
+                async move {
+                    client
+                        .upload_file_chunk(app_slug, path, chunk, tracker)
+                        .await?;
+                    tracker.chunk_uploaded(chunk.number, chunk.total);
+                    Ok(())
+                }
+            })
+            .await?;
+
+        tracker.finished();
+
+        if let Some(state_file) = &options.state_file {
+            UploadState::update(state_file, |state| {
+                state.sessions.remove(&state_key);
+            })
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Opens a new upload session for a file.
+    pub async fn new_upload_session(
+        &self,
+        request: NewUploadSessionRequest,
+    ) -> Result<UploadSession> {
+        self.post("apps/upload-sessions/new")
+            .idempotent()
+            .json(&request)?
+            .send()
+            .await
+            .and_then(checked_session)
+    }
+
+    /// Opens an upload session for a content-addressed blob.
+    pub async fn new_blob_session(&self, request: NewBlobRequest) -> Result<UploadSession> {
+        self.post("apps/blobs/new")
+            .idempotent()
+            .json(&request)?
+            .send()
+            .await
+            .and_then(checked_session)
+    }
+
+    /// Fetches an upload session including the chunks the server has already received.
+    pub async fn upload_session(&self, session_id: &str) -> Result<UploadSession> {
+        self.post("apps/upload-sessions/status")
+            .idempotent()
+            .json(&UploadSessionRequest {
+                session_id: session_id.to_string(),
+            })?
+            .send()
+            .await
+            .and_then(checked_session)
+    }
+
+    /// Opens a session for `target`. The hash of a file is optional, the hash of a blob is
+    /// taken from `target`.
+    async fn open_session(
+        &self,
+        app_slug: &str,
+        file_name: &str,
+        size: u64,
+        target: UploadTarget<'_>,
+        sha256: Option<String>,
+    ) -> Result<UploadSession> {
+        match target {
+            UploadTarget::File => {
+                self.new_upload_session(NewUploadSessionRequest {
+                    app_slug: app_slug.to_string(),
+                    file_name: file_name.to_string(),
+                    size,
+                    sha256,
+                    chunk_size: UPLOAD_CHUNK_SIZE,
+                })
+                .await
+            }
+            UploadTarget::Blob { sha256 } => {
+                self.new_blob_session(NewBlobRequest {
+                    app_slug: app_slug.to_string(),
+                    sha256: sha256.to_string(),
+                    size,
+                    chunk_size: UPLOAD_CHUNK_SIZE,
+                })
+                .await
+            }
+        }
+    }
+
+    /// Streams a chunk of the file at `path` without buffering it in memory. Since the file can
+    /// simply be read again, failed chunks are retried according to the [`RetryPolicy`].
+    ///
+    /// [`RetryPolicy`]: crate::RetryPolicy
+    async fn upload_file_chunk(
+        &self,
+        app_slug: &str,
+        path: &Path,
+        chunk: Chunk<'_>,
+        tracker: &ProgressTracker,
+    ) -> Result<()> {
+        let file_name = path.to_string_lossy().to_string();
+        let io_error = |source| ClientError::Io {
+            path: path.to_path_buf(),
+            source,
+        };
+
+        self.inner
+            .retry_policy
+            .run(|| async {
+                let mut file = tokio::fs::File::open(path).await.map_err(io_error)?;
+                file.seek(SeekFrom::Start(chunk.offset))
+                    .await
+                    .map_err(io_error)?;
+
+                let (stream, attempt_sent) = tracker.track(ReaderStream::with_capacity(
+                    file.take(chunk.len),
+                    STREAM_BUFFER_SIZE,
+                ));
+
+                let result = self
+                    .send_chunk(
+                        app_slug,
+                        file_name.clone(),
+                        chunk,
+                        reqwest::Body::wrap_stream(stream),
+                    )
+                    .await;
+
+                if result.is_err() {
+                    tracker.rewind(&attempt_sent);
+                }
+                result
+            })
+            .await
+    }
+
+    async fn send_chunk(
+        &self,
+        app_slug: &str,
+        file_name: String,
+        chunk: Chunk<'_>,
+        body: reqwest::Body,
+    ) -> Result<()> {
+        let part =
+            reqwest::multipart::Part::stream_with_length(body, chunk.len).file_name(file_name);
+
+        let form = reqwest::multipart::Form::new()
+            .part("file", part)
+            .text("session_id", chunk.session_id.to_string())
+            .text("chunk_number", chunk.number.to_string())
+            .text("total_chunks", chunk.total.to_string());
+
+        let _: SuccessResponse = self
+            .post("apps/upload-file")
+            .multipart(form)
+            .header(
+                AppMeta::name(),
+                AppMeta {
+                    app_slug: app_slug.to_string(),
+                }
+                .to_string_value(),
+            )
+            .send()
+            .await?;
+
+        Ok(())
+    }
+
+    /// Uploads `len` bytes read from `reader` as a file called `file_name`, e.g. for generated
+    /// content that doesn't exist on disk.
+    ///
+    /// The content is streamed chunk by chunk and never held in memory as a whole. Since the
+    /// reader can't be rewound, failed chunks aren't retried and the upload can't be resumed.
+    pub async fn upload_reader<R>(
+        &self,
+        app_slug: impl AsRef<str>,
+        file_name: impl AsRef<str>,
+        reader: R,
+        len: u64,
+    ) -> Result<()>
+    where
+        R: AsyncRead + Unpin,
+    {
+        self.upload_reader_with_options(app_slug, file_name, reader, len, &UploadOptions::default())
+            .await
+    }
+
+    /// Like [`Client::upload_reader`] but reports progress to [`UploadOptions::progress`]. The
+    /// other options don't apply to readers.
+    pub async fn upload_reader_with_options<R>(
+        &self,
+        app_slug: impl AsRef<str>,
+        file_name: impl AsRef<str>,
+        reader: R,
+        len: u64,
+        options: &UploadOptions,
+    ) -> Result<()>
+    where
+        R: AsyncRead + Unpin,
+    {
+        self.upload_reader_in_session(
+            app_slug.as_ref(),
+            file_name.as_ref(),
+            reader,
+            len,
+            UploadTarget::File,
+            options,
+        )
+        .await
+    }
+
+    /// Uploads `len` bytes read from `reader` as a content-addressed blob. Like
+    /// [`Client::upload_reader`] but for blobs missing from a manifest.
+    pub async fn upload_blob_reader<R>(
+        &self,
+        app_slug: impl AsRef<str>,
+        sha256: &str,
+        reader: R,
+        len: u64,
+    ) -> Result<()>
+    where
+        R: AsyncRead + Unpin,
+    {
+        self.upload_blob_reader_with_options(
+            app_slug,
+            sha256,
+            reader,
+            len,
+            &UploadOptions::default(),
+        )
+        .await
+    }
+
+    /// Like [`Client::upload_blob_reader`] but reports progress to [`UploadOptions::progress`].
+    pub async fn upload_blob_reader_with_options<R>(
+        &self,
+        app_slug: impl AsRef<str>,
+        sha256: &str,
+        reader: R,
+        len: u64,
+        options: &UploadOptions,
+    ) -> Result<()>
+    where
+        R: AsyncRead + Unpin,
+    {
+        self.upload_reader_in_session(
+            app_slug.as_ref(),
+            sha256,
+            reader,
+            len,
+            UploadTarget::Blob { sha256 },
+            options,
+        )
+        .await
+    }
+
+    async fn upload_reader_in_session<R>(
+        &self,
+        app_slug: &str,
+        file_name: &str,
+        mut reader: R,
+        len: u64,
+        target: UploadTarget<'_>,
+        options: &UploadOptions,
+    ) -> Result<()>
+    where
+        R: AsyncRead + Unpin,
+    {
+        let io_error = |source| ClientError::Io {
+            path: PathBuf::from(file_name),
+            source,
+        };
+
+        let session = self
+            .open_session(app_slug, file_name, len, target, None)
+            .await?;
+        let total_chunks = chunk_count(len, session.chunk_size);
+
+        let tracker = ProgressTracker::new(file_name, len, options.progress.clone());
+        tracker.started(0, total_chunks);
+
+        for number in 0..total_chunks {
+            let chunk = Chunk::new(&session, number, len);
+
+            // The request body reads from one end of the pipe while the reader is copied into
+            // the other, so at most the pipe's buffer is held in memory.
+            let (mut writer, body_reader) = tokio::io::duplex(STREAM_BUFFER_SIZE);
+            let (stream, _) =
+                tracker.track(ReaderStream::with_capacity(body_reader, STREAM_BUFFER_SIZE));
+            let body = reqwest::Body::wrap_stream(stream);
+
+            let copy = async {
+                let copied = tokio::io::copy(&mut (&mut reader).take(chunk.len), &mut writer)
+                    .await
+                    .map_err(io_error)?;
+                drop(writer);
+
+                if copied < chunk.len {
+                    return Err(io_error(std::io::ErrorKind::UnexpectedEof.into()));
+                }
+                Ok(())
+            };
+            let send = self.send_chunk(app_slug, file_name.to_string(), chunk, body);
+
+            tokio::try_join!(copy, send)?;
+            tracker.chunk_uploaded(chunk.number, chunk.total);
+        }
+
+        tracker.finished();
+
+        Ok(())
+    }
+}
+
+/// Position of a single chunk within an upload session.
+#[derive(Clone, Copy)]
+struct Chunk<'a> {
+    session_id: &'a str,
+    number: u64,
+    total: u64,
+    offset: u64,
+    len: u64,
+}
+
+impl<'a> Chunk<'a> {
+    fn new(session: &'a UploadSession, number: u64, size: u64) -> Self {
+        let offset = number * session.chunk_size;
+
+        Self {
+            session_id: &session.session_id,
+            number,
+            total: chunk_count(size, session.chunk_size),
+            offset,
+            len: std::cmp::min(session.chunk_size, size - offset),
+        }
+    }
+}
+
+/// Number of chunks of an upload of `size` bytes. An empty upload is a single empty chunk, so
+/// that the server still receives a request that completes the session.
+fn chunk_count(size: u64, chunk_size: u64) -> u64 {
+    size.div_ceil(chunk_size).max(1)
+}
+
+/// Rejects sessions that would make the chunk arithmetic divide by zero.
+fn checked_session(session: UploadSession) -> Result<UploadSession> {
+    if session.chunk_size == 0 {
+        return Err(ClientError::InvalidChunkSize {
+            session_id: session.session_id,
+        });
+    }
+
+    Ok(session)
+}
+
+#[derive(Clone, Copy)]
+enum UploadTarget<'a> {
+    /// A file that is stored under its path.
+    File,
+    /// A blob that is stored under its hash.
+    Blob { sha256: &'a str },
+}
+
+/// Cheap way of telling whether a file changed since an upload session was opened for it.
+struct FileFingerprint {
+    size: u64,
+    modified: Option<u64>,
+}
+
+impl FileFingerprint {
+    async fn read(path: &Path) -> Result<Self> {
+        let metadata = tokio::fs::metadata(path)
+            .await
+            .map_err(|source| ClientError::Io {
+                path: path.to_path_buf(),
+                source,
+            })?;
+
+        Ok(Self {
+            size: metadata.len(),
+            modified: metadata
+                .modified()
+                .ok()
+                .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+                .map(|modified| modified.as_secs()),
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct UploadState {
+    sessions: HashMap<String, StateEntry>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct StateEntry {
+    session_id: String,
+    sha256: String,
+    size: u64,
+    modified: Option<u64>,
+}
+
+impl StateEntry {
+    fn matches(&self, fingerprint: &FileFingerprint) -> bool {
+        self.size == fingerprint.size
+            && self.modified.is_some()
+            && self.modified == fingerprint.modified
+    }
+}
+
+impl UploadState {
+    /// Loads the state file. A missing or corrupt state file just means nothing can be resumed.
+    async fn load(path: &Path) -> Self {
+        let _guard = STATE_FILE_LOCK.lock().await;
+        Self::load_unlocked(path).await
+    }
+
+    async fn load_unlocked(path: &Path) -> Self {
+        tokio::fs::read(path)
+            .await
+            .ok()
+            .and_then(|contents| serde_json::from_slice(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    async fn update(path: &Path, f: impl FnOnce(&mut Self)) -> Result<()> {
+        let _guard = STATE_FILE_LOCK.lock().await;
+
+        let mut state = Self::load_unlocked(path).await;
+        f(&mut state);
+
+        let io_error = |source| ClientError::Io {
+            path: path.to_path_buf(),
+            source,
+        };
+
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await.map_err(io_error)?;
+        }
+        tokio::fs::write(path, serde_json::to_vec_pretty(&state)?)
+            .await
+            .map_err(io_error)
+    }
+}
+
+/// Computes the hex encoded SHA-256 hash of a file without loading it into memory at once.
+pub async fn sha256_file(path: &Path) -> Result<String> {
+    let io_error = |source| ClientError::Io {
+        path: path.to_path_buf(),
+        source,
+    };
+
+    let mut file = tokio::fs::File::open(path).await.map_err(io_error)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 64 * 1024];
+
+    loop {
+        let n = file.read(&mut buffer).await.map_err(io_error)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buffer[..n]);
+    }
+
+    Ok(format!("{:x}", hasher.finalize()))
+}
@@ -0,0 +1,170 @@
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use axum::Router;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use oxyde_cloud_client::{Client, ClientError, RetryPolicy};
+use oxyde_cloud_common::net::{CheckAvailabilityResponse, LogRequest, NewTeamRequest};
+
+type Hits = Arc<Mutex<HashMap<&'static str, usize>>>;
+
+/// Starts the fake API once for all tests in this file and returns its hit counters and address.
+fn server() -> &'static (Hits, SocketAddr) {
+    static SERVER: OnceLock<(Hits, SocketAddr)> = OnceLock::new();
+
+    SERVER.get_or_init(|| {
+        let hits = Hits::default();
+        let (addr_tx, addr_rx) = std::sync::mpsc::channel();
+
+        let app = Router::new()
+            .route("/teams", get(flaky_teams))
+            .route("/teams/new", post(flaky_new_team))
+            .route(
+                "/apps/new",
+                post(|State(hits)| unavailable(hits, "apps/new")),
+            )
+            .route("/login", post(rate_limited_login))
+            .route("/log", post(|State(hits)| unavailable(hits, "log")))
+            .with_state(hits.clone());
+
+        std::thread::spawn(move || {
+            let runtime = tokio::runtime::Runtime::new().unwrap();
+            runtime.block_on(async move {
+                let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+                addr_tx.send(listener.local_addr().unwrap()).unwrap();
+                axum::serve(listener, app).await.unwrap();
+            });
+        });
+
+        let addr = addr_rx.recv().unwrap();
+
+        (hits, addr)
+    })
+}
+
+fn hit(hits: &Hits, route: &'static str) -> usize {
+    let mut hits = hits.lock().unwrap();
+    let count = hits.entry(route).or_default();
+    *count += 1;
+    *count
+}
+
+fn hit_count(route: &'static str) -> usize {
+    server()
+        .0
+        .lock()
+        .unwrap()
+        .get(route)
+        .copied()
+        .unwrap_or_default()
+}
+
+async fn flaky_teams(State(hits): State<Hits>) -> Response {
+    match hit(&hits, "teams") {
+        1 => StatusCode::BAD_GATEWAY.into_response(),
+        2 => StatusCode::SERVICE_UNAVAILABLE.into_response(),
+        _ => axum::Json(Vec::<()>::new()).into_response(),
+    }
+}
+
+async fn flaky_new_team(State(hits): State<Hits>) -> Response {
+    match hit(&hits, "teams/new") {
+        1 => StatusCode::GATEWAY_TIMEOUT.into_response(),
+        _ => axum::Json(CheckAvailabilityResponse { available: true }).into_response(),
+    }
+}
+
+async fn unavailable(hits: Hits, route: &'static str) -> Response {
+    hit(&hits, route);
+
+    (
+        StatusCode::SERVICE_UNAVAILABLE,
+        r#"{"error":"maintenance","code":"unavailable"}"#,
+    )
+        .into_response()
+}
+
+async fn rate_limited_login(State(hits): State<Hits>) -> Response {
+    match hit(&hits, "login") {
+        1 => (StatusCode::TOO_MANY_REQUESTS, [("Retry-After", "1")]).into_response(),
+        _ => axum::Json(serde_json::json!({ "username": "tester" })).into_response(),
+    }
+}
+
+fn client() -> Client {
+    let (_, addr) = server();
+
+    Client::builder()
+        .base_url(format!("http://{addr}"))
+        .retry_policy(RetryPolicy {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_millis(50),
+            max_retry_after: Duration::from_secs(5),
+        })
+        .build("test-key".to_string())
+        .unwrap()
+}
+
+#[tokio::test]
+async fn retries_gateway_errors() {
+    let teams = client().teams().await.unwrap();
+
+    assert!(teams.is_empty());
+    assert_eq!(hit_count("teams"), 3);
+}
+
+#[tokio::test]
+async fn honors_retry_after() {
+    let start = Instant::now();
+    let login = client().login().await.unwrap();
+
+    assert_eq!(login.username, "tester");
+    assert_eq!(hit_count("login"), 2);
+    assert!(start.elapsed() >= Duration::from_secs(1));
+}
+
+#[tokio::test]
+async fn gives_up_after_max_retries() {
+    let err = client().log(&LogRequest::new("my-app")).await.unwrap_err();
+
+    assert!(matches!(err, ClientError::Server { .. }));
+    assert_eq!(
+        err.server_error().unwrap().code.as_deref(),
+        Some("unavailable")
+    );
+    assert_eq!(hit_count("log"), 4);
+}
+
+#[tokio::test]
+async fn does_not_retry_non_idempotent_requests() {
+    let err = client()
+        .new_app("my-app", "my-team", "My App")
+        .await
+        .unwrap_err();
+
+    assert_eq!(err.route(), Some("apps/new"));
+    assert_eq!(hit_count("apps/new"), 1);
+}
+
+#[tokio::test]
+async fn retries_requests_with_idempotency_key() {
+    let CheckAvailabilityResponse { available } = client()
+        .post("teams/new")
+        .idempotency_key("create-my-team")
+        .json(NewTeamRequest {
+            team_slug: "my-team".to_string(),
+        })
+        .unwrap()
+        .send()
+        .await
+        .unwrap();
+
+    assert!(available);
+    assert_eq!(hit_count("teams/new"), 2);
+}
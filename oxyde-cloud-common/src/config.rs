@@ -1,6 +1,11 @@
 use anyhow::{Context, Result};
 
+use crate::health::HealthConfig;
+use crate::runtime::RuntimeConfig;
+use crate::secrets::{MalformedSecretReferences, secret_references};
+
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
 use std::path::PathBuf;
 use toml::Table;
 
@@ -10,8 +15,90 @@ pub struct CloudConfig {
 
     pub env: Table,
 
+    /// Instances, resources and regions of the app. The cloud's defaults are used if it's left
+    /// out.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub runtime: Option<RuntimeConfig>,
+
+    /// How the cloud checks that a new version of the app is healthy. The cloud's defaults are
+    /// used if it's left out.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub health: Option<HealthConfig>,
+
+    /// Custom domains the app is served on in addition to its `oxydecloud.com` subdomain.
+    #[serde(default, skip_serializing_if = "DomainsConfig::is_empty")]
+    pub domains: DomainsConfig,
+
     #[serde(skip)]
     pub leptos_config: Option<leptos_config::ConfFile>,
+
+    /// Domains of [`CloudConfig::domains`] whose DNS records have been verified. Only the API
+    /// knows these, so they have to be filled in before [`CloudConfig::deployed_url`] can
+    /// return a custom domain.
+    #[serde(skip)]
+    pub verified_domains: BTreeSet<String>,
+}
+
+/// The `[domains]` section of `oxyde-cloud.toml`.
+///
+/// ```toml
+/// [domains]
+/// names = ["example.com", "www.example.com"]
+/// primary = "example.com"
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct DomainsConfig {
+    #[serde(default)]
+    pub names: Vec<String>,
+
+    /// The domain the app is reachable at primarily. Defaults to the first of `names`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub primary: Option<String>,
+}
+
+impl DomainsConfig {
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty() && self.primary.is_none()
+    }
+
+    pub fn primary(&self) -> Option<&str> {
+        self.primary
+            .as_deref()
+            .or(self.names.first().map(String::as_str))
+    }
+
+    /// Whether `domain` looks like a fully qualified domain name like `www.example.com`.
+    pub fn is_valid_domain(domain: &str) -> bool {
+        domain.len() <= 253
+            && domain.contains('.')
+            && domain.split('.').all(|label| {
+                !label.is_empty()
+                    && label.len() <= 63
+                    && !label.starts_with('-')
+                    && !label.ends_with('-')
+                    && label
+                        .chars()
+                        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+            })
+    }
+
+    fn validate(&self) -> Result<()> {
+        for name in &self.names {
+            if !Self::is_valid_domain(name) {
+                anyhow::bail!(
+                    "Invalid domain '{name}' in [domains]. Domains have to be lower case like 'www.example.com'"
+                );
+            }
+        }
+
+        if let Some(primary) = &self.primary
+            && !self.names.contains(primary)
+        {
+            anyhow::bail!("The primary domain '{primary}' is missing in [domains] names");
+        }
+
+        Ok(())
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -48,6 +135,11 @@ impl AppConfig {
                 .chars()
                 .all(|c| Self::ALLOWED_CHARS.contains(&c))
     }
+
+    /// The URL of the `oxydecloud.com` subdomain of the app `slug`.
+    pub fn default_url(slug: &str) -> String {
+        format!("https://{slug}.oxydecloud.com")
+    }
 }
 
 // Error type replaced with anyhow::Result for better error handling
@@ -60,6 +152,23 @@ impl CloudConfig {
         let mut config: Self = toml::from_str(&contents)
             .with_context(|| format!("Failed to parse TOML config file: {}", path.display()))?;
 
+        config
+            .domains
+            .validate()
+            .with_context(|| format!("Invalid config file: {}", path.display()))?;
+
+        if let Some(runtime) = &config.runtime {
+            runtime
+                .validate()
+                .with_context(|| format!("Invalid config file: {}", path.display()))?;
+        }
+
+        if let Some(health) = &config.health {
+            health
+                .validate()
+                .with_context(|| format!("Invalid config file: {}", path.display()))?;
+        }
+
         config.leptos_config = Some(
             leptos_config::get_configuration(Some("Cargo.toml"))
                 .context("Failed to load Leptos configuration")?,
@@ -68,7 +177,26 @@ impl CloudConfig {
         Ok(config)
     }
 
+    /// The names of all secrets referenced as `${{ secrets.NAME }}` in the `[env]` section.
+    pub fn secret_references(
+        &self,
+    ) -> std::result::Result<BTreeSet<String>, MalformedSecretReferences> {
+        secret_references(&self.env)
+    }
+
+    /// The URL of the primary custom domain once it's verified, otherwise the one of the
+    /// `oxydecloud.com` subdomain.
     pub fn deployed_url(&self) -> String {
-        format!("https://{}.oxydecloud.com", self.app.slug)
+        match self.domains.primary() {
+            Some(primary) if self.verified_domains.contains(primary) => {
+                format!("https://{primary}")
+            }
+            _ => self.default_url(),
+        }
+    }
+
+    /// The URL of the `oxydecloud.com` subdomain every app is reachable at.
+    pub fn default_url(&self) -> String {
+        AppConfig::default_url(&self.app.slug)
     }
 }
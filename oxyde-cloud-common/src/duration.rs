@@ -0,0 +1,81 @@
+//! Durations in `oxyde-cloud.toml` written like `30s`, `15m`, `2h` or `1d`.
+//!
+//! Use [`serde`] or [`option`] with `#[serde(with = "...")]` on `Duration` fields.
+
+use std::time::Duration;
+
+/// Parses a duration like `30s`, `15m`, `2h` or `1d`.
+pub fn parse_duration(value: &str) -> Option<Duration> {
+    let split = value.find(|c: char| !c.is_ascii_digit())?;
+    let (amount, unit) = value.split_at(split);
+    let amount = amount.parse::<u64>().ok()?;
+
+    let seconds = match unit {
+        "s" => Some(amount),
+        "m" => amount.checked_mul(60),
+        "h" => amount.checked_mul(60 * 60),
+        "d" => amount.checked_mul(24 * 60 * 60),
+        _ => None,
+    }?;
+
+    Some(Duration::from_secs(seconds))
+}
+
+/// Formats a duration in the largest unit that represents it exactly, e.g. `15m` for 900
+/// seconds. Fractions of seconds are dropped.
+pub fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+
+    for (unit, size) in [("d", 24 * 60 * 60), ("h", 60 * 60), ("m", 60)] {
+        if seconds > 0 && seconds.is_multiple_of(size) {
+            return format!("{}{unit}", seconds / size);
+        }
+    }
+
+    format!("{seconds}s")
+}
+
+pub mod serde {
+    use std::time::Duration;
+
+    use serde::{Deserialize, Deserializer, Serializer, de::Error};
+
+    pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&super::format_duration(*duration))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        let value = String::deserialize(deserializer)?;
+
+        super::parse_duration(&value).ok_or_else(|| {
+            D::Error::custom(format!(
+                "invalid duration `{value}`, expected something like `30s`, `15m`, `2h` or `1d`"
+            ))
+        })
+    }
+}
+
+pub mod option {
+    use std::time::Duration;
+
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        duration: &Option<Duration>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match duration {
+            Some(duration) => super::serde::serialize(duration, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Duration>, D::Error> {
+        #[derive(Deserialize)]
+        struct Wrapper(#[serde(with = "super::serde")] Duration);
+
+        Ok(Option::<Wrapper>::deserialize(deserializer)?.map(|Wrapper(duration)| duration))
+    }
+}
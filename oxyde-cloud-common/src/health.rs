@@ -0,0 +1,109 @@
+//! The `[health]` section of `oxyde-cloud.toml` which tells the cloud when a new version of the
+//! app is healthy.
+//!
+//! ```toml
+//! [health]
+//! path = "/healthz"
+//! expected_status = 200
+//! interval = "10s"
+//! timeout = "5s"
+//! grace_period = "30s"
+//! ```
+
+use std::time::Duration;
+
+use anyhow::{Result, bail};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct HealthConfig {
+    /// Path that is requested with `GET` to check the app.
+    #[serde(default = "HealthConfig::default_path")]
+    pub path: String,
+
+    /// Status code a healthy app responds with.
+    #[serde(default = "HealthConfig::default_expected_status")]
+    pub expected_status: u16,
+
+    /// Time between two checks.
+    #[serde(
+        default = "HealthConfig::default_interval",
+        with = "crate::duration::serde"
+    )]
+    pub interval: Duration,
+
+    /// A check fails if the app takes longer than this to respond.
+    #[serde(
+        default = "HealthConfig::default_timeout",
+        with = "crate::duration::serde"
+    )]
+    pub timeout: Duration,
+
+    /// Failed checks are ignored for this long after an instance started, so the app has time
+    /// to start up.
+    #[serde(
+        default = "HealthConfig::default_grace_period",
+        with = "crate::duration::serde"
+    )]
+    pub grace_period: Duration,
+}
+
+impl HealthConfig {
+    fn default_path() -> String {
+        "/".to_string()
+    }
+
+    fn default_expected_status() -> u16 {
+        200
+    }
+
+    fn default_interval() -> Duration {
+        Duration::from_secs(10)
+    }
+
+    fn default_timeout() -> Duration {
+        Duration::from_secs(5)
+    }
+
+    fn default_grace_period() -> Duration {
+        Duration::from_secs(30)
+    }
+
+    /// The URL that is checked on the app at `base_url`.
+    pub fn url(&self, base_url: &str) -> String {
+        format!("{}{}", base_url.trim_end_matches('/'), self.path)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if !self.path.starts_with('/') {
+            bail!("health.path has to start with '/'");
+        }
+        if !(100..=599).contains(&self.expected_status) {
+            bail!(
+                "health.expected_status {} isn't an HTTP status code",
+                self.expected_status
+            );
+        }
+        if self.interval.is_zero() || self.timeout.is_zero() {
+            bail!("health.interval and health.timeout have to be longer than 0s");
+        }
+        if self.timeout > self.interval {
+            bail!("health.timeout can't be longer than health.interval");
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for HealthConfig {
+    fn default() -> Self {
+        Self {
+            path: Self::default_path(),
+            expected_status: Self::default_expected_status(),
+            interval: Self::default_interval(),
+            timeout: Self::default_timeout(),
+            grace_period: Self::default_grace_period(),
+        }
+    }
+}
@@ -1,2 +1,6 @@
 pub mod config;
+pub mod duration;
+pub mod health;
 pub mod net;
+pub mod runtime;
+pub mod secrets;
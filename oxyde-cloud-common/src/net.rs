@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 use headers_core::{Header, HeaderName, HeaderValue};
 use serde::{Deserialize, Serialize};
 
@@ -19,6 +20,26 @@ pub struct NewAppRequest {
     pub name: String,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct App {
+    pub slug: String,
+    pub name: String,
+    /// Slug of the team that owns the app.
+    pub team_slug: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DeleteAppRequest {
+    pub app_slug: String,
+}
+
+/// Moves an app to another team the user is a member of.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TransferAppRequest {
+    pub app_slug: String,
+    pub team_slug: String,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct NewTeamRequest {
     pub team_slug: String,
@@ -35,14 +56,211 @@ pub struct SetTeamNameRequest {
     pub team_name: String,
 }
 
+/// What a member can do within a team. Every role includes the permissions of the roles after
+/// it.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum TeamRole {
+    /// Manages members and can delete the team.
+    Owner,
+    /// Manages apps, secrets and members except owners.
+    Admin,
+    /// Deploys and rolls back apps.
+    Deployer,
+    /// Reads apps, deployments and logs.
+    Viewer,
+}
+
+impl TeamRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TeamRole::Owner => "owner",
+            TeamRole::Admin => "admin",
+            TeamRole::Deployer => "deployer",
+            TeamRole::Viewer => "viewer",
+        }
+    }
+}
+
+impl std::fmt::Display for TeamRole {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for TeamRole {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "owner" => Ok(TeamRole::Owner),
+            "admin" => Ok(TeamRole::Admin),
+            "deployer" => Ok(TeamRole::Deployer),
+            "viewer" => Ok(TeamRole::Viewer),
+            _ => Err(format!(
+                "Unknown role `{s}`. Expected one of owner, admin, deployer or viewer"
+            )),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TeamMember {
+    pub username: String,
+    pub role: TeamRole,
+    pub joined_at: DateTime<Utc>,
+}
+
+/// An invitation to join a team that hasn't been accepted yet.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TeamInvitation {
+    pub id: String,
+    pub email: String,
+    /// The role the invitee gets once they accept.
+    pub role: TeamRole,
+    /// Username of whoever sent the invitation.
+    pub invited_by: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Sends an invitation email to join a team.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct InviteMemberRequest {
+    pub team_slug: String,
+    pub email: String,
+    pub role: TeamRole,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RemoveMemberRequest {
+    pub team_slug: String,
+    pub username: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SetMemberRoleRequest {
+    pub team_slug: String,
+    pub username: String,
+    pub role: TeamRole,
+}
+
+/// Selects the entries of a log. All filters are optional and combined, so an entry has to match
+/// every filter that is set.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct LogRequest {
     pub name: String,
+
+    /// Only entries written at or after this time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub since: Option<DateTime<Utc>>,
+
+    /// Only entries written before this time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub until: Option<DateTime<Utc>>,
+
+    /// Only the last `tail` of the matching entries.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tail: Option<usize>,
+
+    /// Only entries of this level or more severe.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub level: Option<LogLevel>,
+
+    /// Only entries of the app instance with this id.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instance: Option<String>,
+
+    /// Only entries whose message matches.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter: Option<LogFilter>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct LogResponse {
-    pub log: String,
+impl LogRequest {
+    /// Requests the whole log called `name`.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFilter {
+    /// The message contains this text.
+    Text(String),
+    /// The message matches this regular expression.
+    Regex(String),
+}
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl LogLevel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LogLevel::Trace => "trace",
+            LogLevel::Debug => "debug",
+            LogLevel::Info => "info",
+            LogLevel::Warn => "warn",
+            LogLevel::Error => "error",
+        }
+    }
+}
+
+impl std::fmt::Display for LogLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for LogLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "trace" => Ok(LogLevel::Trace),
+            "debug" => Ok(LogLevel::Debug),
+            "info" => Ok(LogLevel::Info),
+            "warn" | "warning" => Ok(LogLevel::Warn),
+            "error" => Ok(LogLevel::Error),
+            _ => Err(format!(
+                "Unknown log level `{s}`. Expected one of trace, debug, info, warn or error"
+            )),
+        }
+    }
+}
+
+/// The output an app wrote a log entry to.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputStream {
+    Stdout,
+    Stderr,
+}
+
+/// A single entry of a log. The `log` route responds with the matching entries in the order they
+/// were written.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct LogEntry {
+    pub timestamp: DateTime<Utc>,
+    pub level: LogLevel,
+    pub stream: OutputStream,
+    /// Id of the app instance that wrote the entry.
+    pub instance: String,
+    pub message: String,
+    /// Opaque position of the entry in a followed log to resume the stream right after it. Only
+    /// set on the entries of `log/stream`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -92,6 +310,68 @@ pub enum DeploymentStatus {
     Failure = 2,
 }
 
+impl DeploymentStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DeploymentStatus::Pending => "pending",
+            DeploymentStatus::Success => "success",
+            DeploymentStatus::Failure => "failure",
+        }
+    }
+}
+
+impl std::fmt::Display for DeploymentStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct UploadDoneResponse {
+    /// Id of the deployment that was started. Its status tells whether it went live.
+    pub deployment_id: String,
+}
+
+/// Redeploys the files of an earlier successful deployment as a new deployment.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RollbackRequest {
+    pub app_slug: String,
+    pub deployment_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RollbackResponse {
+    /// Id of the new deployment that was started.
+    pub deployment_id: String,
+}
+
+/// A single deployment of an app, i.e. one `upload-done` after uploading its files.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Deployment {
+    pub id: String,
+    pub app_slug: String,
+    pub status: DeploymentStatus,
+    pub created_at: DateTime<Utc>,
+    /// Username of whoever started the deployment.
+    pub creator: String,
+    /// Git commit the deployment was built from, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit_sha: Option<String>,
+    /// Total size of all files of the deployment in bytes.
+    pub size: u64,
+    /// For rollbacks, the id of the deployment whose files were redeployed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rolled_back_from: Option<String>,
+}
+
+impl Deployment {
+    /// Id of the deployment that originally uploaded the files this deployment runs. That's the
+    /// deployment itself unless it's a rollback.
+    pub fn release_id(&self) -> &str {
+        self.rolled_back_from.as_deref().unwrap_or(&self.id)
+    }
+}
+
 impl From<i16> for DeploymentStatus {
     fn from(value: i16) -> Self {
         match value {
@@ -102,6 +382,26 @@ impl From<i16> for DeploymentStatus {
     }
 }
 
+/// Error payload returned by the API for every non-success response.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ErrorResponse {
+    /// Human readable description of what went wrong.
+    pub error: String,
+
+    /// Machine readable error code like `"slug_taken"`, if the server provides one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+}
+
+impl std::fmt::Display for ErrorResponse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.code {
+            Some(code) => write!(f, "{} ({code})", self.error),
+            None => write!(f, "{}", self.error),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct SuccessResponse {
     pub success: bool,
@@ -112,3 +412,402 @@ impl Default for SuccessResponse {
         Self { success: true }
     }
 }
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NewUploadSessionRequest {
+    pub app_slug: String,
+    pub file_name: String,
+    /// Total size of the file in bytes.
+    pub size: u64,
+    /// Hex encoded SHA-256 hash of the whole file. Unknown for content that is streamed from
+    /// a reader.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+    pub chunk_size: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct UploadSessionRequest {
+    pub session_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct UploadSession {
+    pub session_id: String,
+    pub chunk_size: u64,
+    /// Numbers of the chunks the server has already received and verified.
+    #[serde(default)]
+    pub received_chunks: Vec<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NewBlobRequest {
+    pub app_slug: String,
+    /// Hex encoded SHA-256 hash of the blob. Blobs are stored and deduplicated by this hash.
+    pub sha256: String,
+    pub size: u64,
+    pub chunk_size: u64,
+}
+
+/// Describes every file of a deployment so the server only needs the blobs it doesn't have yet.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DeployManifest {
+    pub app_slug: String,
+    pub files: Vec<ManifestEntry>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ManifestEntry {
+    /// Path relative to the deployment root. Frontend files live under `site/`, server binaries
+    /// are referenced by their bare file name.
+    pub path: String,
+    /// Hex encoded SHA-256 hash of the file contents.
+    pub sha256: String,
+    pub size: u64,
+    /// Unix permission bits like `0o755`.
+    pub mode: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ManifestResponse {
+    /// Hashes of the blobs referenced by the manifest that the server doesn't have yet.
+    pub missing_blobs: Vec<String>,
+}
+
+/// Follows a log. `log/stream` responds with the matching [`LogEntry`]s as newline delimited JSON,
+/// each with its cursor, and keeps the response open for new entries. The filters work like the
+/// ones of [`LogRequest`].
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct LogStreamRequest {
+    pub name: String,
+
+    /// Cursor of the last entry the client has seen. The stream continues right after it, or at
+    /// the beginning of the log if there is none.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
+
+    /// Only entries written at or after this time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub since: Option<DateTime<Utc>>,
+
+    /// Start with the last `tail` of the matching entries already written. Ignored when resuming
+    /// after a cursor.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tail: Option<usize>,
+
+    /// Only entries of this level or more severe.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub level: Option<LogLevel>,
+
+    /// Only entries of the app instance with this id.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instance: Option<String>,
+
+    /// Only entries whose message matches.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter: Option<LogFilter>,
+}
+
+impl LogStreamRequest {
+    /// Follows the whole log called `name` from its beginning.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ..Default::default()
+        }
+    }
+}
+
+/// What a secret of the secret store belongs to. Team secrets are available to all apps of
+/// the team, app secrets take precedence over team secrets of the same name.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum SecretScope {
+    App(String),
+    Team(String),
+}
+
+impl std::fmt::Display for SecretScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SecretScope::App(slug) => write!(f, "app '{slug}'"),
+            SecretScope::Team(slug) => write!(f, "team '{slug}'"),
+        }
+    }
+}
+
+/// Creates a secret or replaces its value.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SetSecretRequest {
+    pub scope: SecretScope,
+    pub name: String,
+    pub value: String,
+}
+
+impl std::fmt::Debug for SetSecretRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SetSecretRequest")
+            .field("scope", &self.scope)
+            .field("name", &self.name)
+            .field("value", &"<redacted>")
+            .finish()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SecretsRequest {
+    pub scope: SecretScope,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DeleteSecretRequest {
+    pub scope: SecretScope,
+    pub name: String,
+}
+
+/// A secret as listed by the API. Values are write-only and never returned.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Secret {
+    pub name: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Asks which of the secrets an app references don't exist. Both the secrets of the app and
+/// those of its team count.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CheckSecretsRequest {
+    pub app_slug: String,
+    pub names: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CheckSecretsResponse {
+    /// The requested names that are neither secrets of the app nor of its team.
+    pub missing: Vec<String>,
+}
+
+/// What an API token is allowed to do.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum TokenScope {
+    /// Read apps, deployments and logs.
+    Read,
+    /// Deploy and roll back apps.
+    Deploy,
+    /// Set and delete secrets.
+    Secrets,
+    /// Everything the owner of the token can do, including managing apps and teams.
+    Admin,
+}
+
+impl TokenScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TokenScope::Read => "read",
+            TokenScope::Deploy => "deploy",
+            TokenScope::Secrets => "secrets",
+            TokenScope::Admin => "admin",
+        }
+    }
+}
+
+impl std::fmt::Display for TokenScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for TokenScope {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "read" => Ok(TokenScope::Read),
+            "deploy" => Ok(TokenScope::Deploy),
+            "secrets" => Ok(TokenScope::Secrets),
+            "admin" => Ok(TokenScope::Admin),
+            _ => Err(format!(
+                "Unknown token scope `{s}`. Expected one of read, deploy, secrets or admin"
+            )),
+        }
+    }
+}
+
+/// Limits an API token to a single app or the apps of a single team.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TokenRestriction {
+    App(String),
+    Team(String),
+}
+
+impl std::fmt::Display for TokenRestriction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TokenRestriction::App(slug) => write!(f, "app '{slug}'"),
+            TokenRestriction::Team(slug) => write!(f, "team '{slug}'"),
+        }
+    }
+}
+
+/// Creates an API token that can be used instead of the personal API key, e.g. in CI.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NewTokenRequest {
+    /// Describes what the token is used for, like the repository it's stored in.
+    pub name: String,
+
+    pub scopes: Vec<TokenScope>,
+
+    /// Only allow access to this app or team. Tokens without one can access everything their
+    /// owner can.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub restriction: Option<TokenRestriction>,
+
+    /// The token stops working at this time. Tokens without one are valid until revoked.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl NewTokenRequest {
+    /// A request for a token that is neither restricted nor expires.
+    pub fn new(name: impl Into<String>, scopes: impl Into<Vec<TokenScope>>) -> Self {
+        Self {
+            name: name.into(),
+            scopes: scopes.into(),
+            restriction: None,
+            expires_at: None,
+        }
+    }
+}
+
+/// An API token as listed by the API. The secret itself is only returned once on creation.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ApiToken {
+    pub id: String,
+    pub name: String,
+    pub scopes: Vec<TokenScope>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub restriction: Option<TokenRestriction>,
+    pub created_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct NewTokenResponse {
+    pub token: ApiToken,
+
+    /// The value to authenticate with. It can't be retrieved again later.
+    pub secret: String,
+}
+
+impl std::fmt::Debug for NewTokenResponse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NewTokenResponse")
+            .field("token", &self.token)
+            .field("secret", &"<redacted>")
+            .finish()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RevokeTokenRequest {
+    pub id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DomainStatus {
+    /// The DNS records haven't been found yet.
+    Pending,
+    /// The DNS records are in place and the domain routes to the app.
+    Verified,
+}
+
+impl DomainStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DomainStatus::Pending => "pending",
+            DomainStatus::Verified => "verified",
+        }
+    }
+}
+
+impl std::fmt::Display for DomainStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CertificateStatus {
+    /// No certificate is requested before the domain is verified.
+    None,
+    Pending,
+    Issued,
+    Failed,
+}
+
+impl CertificateStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CertificateStatus::None => "none",
+            CertificateStatus::Pending => "pending",
+            CertificateStatus::Issued => "issued",
+            CertificateStatus::Failed => "failed",
+        }
+    }
+}
+
+impl std::fmt::Display for CertificateStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum DnsRecordKind {
+    Txt,
+    Cname,
+}
+
+impl std::fmt::Display for DnsRecordKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DnsRecordKind::Txt => f.write_str("TXT"),
+            DnsRecordKind::Cname => f.write_str("CNAME"),
+        }
+    }
+}
+
+/// A DNS record that has to be set up for a custom domain.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct DnsRecord {
+    pub kind: DnsRecordKind,
+    pub name: String,
+    pub value: String,
+}
+
+/// A custom domain of an app.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Domain {
+    pub name: String,
+    pub app_slug: String,
+    pub status: DomainStatus,
+    pub certificate: CertificateStatus,
+    /// The records the domain needs to be verified and routed to the app.
+    pub records: Vec<DnsRecord>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verified_at: Option<DateTime<Utc>>,
+}
+
+/// Adds, verifies or removes a custom domain of an app.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DomainRequest {
+    pub app_slug: String,
+    pub domain: String,
+}
@@ -0,0 +1,187 @@
+//! The `[runtime]` section of `oxyde-cloud.toml` which declares how the app is run.
+//!
+//! ```toml
+//! [runtime]
+//! instances = { min = 0, max = 3 }
+//! memory = "512MB"
+//! cpu = "shared"
+//! regions = ["fra", "iad"]
+//! sleep_when_idle = "15m"
+//! ```
+
+use std::time::Duration;
+
+use anyhow::{Result, bail};
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de::Error};
+
+/// How the app is run. Settings that are left out use the defaults of the cloud.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct RuntimeConfig {
+    #[serde(default)]
+    pub instances: Instances,
+
+    /// Memory limit of each instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory: Option<Memory>,
+
+    #[serde(default)]
+    pub cpu: CpuClass,
+
+    /// Region codes like `fra` to run instances in. Empty means the default region.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub regions: Vec<String>,
+
+    /// Stop all instances after receiving no requests for this long and start one on the next
+    /// request. Requires `instances.min = 0`.
+    #[serde(
+        default,
+        with = "crate::duration::option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub sleep_when_idle: Option<Duration>,
+}
+
+impl RuntimeConfig {
+    pub const MAX_INSTANCES: u32 = 20;
+
+    pub fn validate(&self) -> Result<()> {
+        let Instances { min, max } = self.instances;
+
+        if max == 0 {
+            bail!("runtime.instances.max has to be at least 1");
+        }
+        if min > max {
+            bail!("runtime.instances.min ({min}) is larger than runtime.instances.max ({max})");
+        }
+        if max > Self::MAX_INSTANCES {
+            bail!(
+                "runtime.instances.max can be at most {}",
+                Self::MAX_INSTANCES
+            );
+        }
+
+        if let Some(memory) = self.memory
+            && !(Memory::MIN..=Memory::MAX).contains(&memory)
+        {
+            bail!(
+                "runtime.memory has to be between {} and {}",
+                Memory::MIN,
+                Memory::MAX
+            );
+        }
+
+        for (index, region) in self.regions.iter().enumerate() {
+            if region.is_empty() || !region.chars().all(|c| c.is_ascii_lowercase()) {
+                bail!("Invalid region '{region}'. Regions are lower case codes like 'fra'");
+            }
+            if self.regions[..index].contains(region) {
+                bail!("Region '{region}' is listed more than once");
+            }
+        }
+
+        if let Some(sleep_when_idle) = self.sleep_when_idle {
+            if min > 0 {
+                bail!("runtime.sleep_when_idle requires runtime.instances.min = 0");
+            }
+            if sleep_when_idle.is_zero() {
+                bail!("runtime.sleep_when_idle has to be longer than 0s");
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// How many instances of the app run at the same time. The cloud scales between `min` and
+/// `max` depending on the load.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct Instances {
+    #[serde(default = "Instances::default_count")]
+    pub min: u32,
+    #[serde(default = "Instances::default_count")]
+    pub max: u32,
+}
+
+impl Instances {
+    fn default_count() -> u32 {
+        1
+    }
+}
+
+impl Default for Instances {
+    fn default() -> Self {
+        Self { min: 1, max: 1 }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CpuClass {
+    /// A share of a CPU core together with other apps.
+    #[default]
+    Shared,
+    /// A CPU core for each instance.
+    Dedicated,
+    /// Dedicated cores with a higher clock rate.
+    Performance,
+}
+
+/// An amount of memory written like `512MB` or `2GB`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Memory {
+    pub megabytes: u64,
+}
+
+impl Memory {
+    pub const MIN: Memory = Memory { megabytes: 128 };
+    pub const MAX: Memory = Memory {
+        megabytes: 32 * 1024,
+    };
+}
+
+impl std::fmt::Display for Memory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.megabytes > 0 && self.megabytes.is_multiple_of(1024) {
+            write!(f, "{}GB", self.megabytes / 1024)
+        } else {
+            write!(f, "{}MB", self.megabytes)
+        }
+    }
+}
+
+impl std::str::FromStr for Memory {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let invalid = || format!("Invalid memory `{s}`. Expected something like `512MB` or `2GB`");
+
+        let split = s.find(|c: char| !c.is_ascii_digit()).ok_or_else(invalid)?;
+        let (amount, unit) = s.split_at(split);
+        let amount = amount.parse::<u64>().map_err(|_| invalid())?;
+
+        let megabytes = match unit.to_ascii_uppercase().as_str() {
+            "MB" => Some(amount),
+            "GB" => amount.checked_mul(1024),
+            _ => None,
+        }
+        .ok_or_else(invalid)?;
+
+        Ok(Memory { megabytes })
+    }
+}
+
+impl Serialize for Memory {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Memory {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(D::Error::custom)
+    }
+}
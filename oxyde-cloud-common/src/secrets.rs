@@ -0,0 +1,172 @@
+//! References to the Oxyde Cloud Secret Store in the `[env]` section of `oxyde-cloud.toml`.
+//!
+//! String values can interpolate secrets with `${{ secrets.NAME }}`, e.g.
+//! `DATABASE_URL = "postgresql://${{ secrets.DATABASE_CREDENTIALS }}@host/db"`. The secrets are
+//! filled in by the cloud when the app starts.
+
+use std::collections::BTreeSet;
+
+use toml::{Table, Value};
+
+const OPEN: &str = "${{";
+const CLOSE: &str = "}}";
+const PREFIX: &str = "secrets.";
+
+/// Whether `name` can be used as a secret name, i.e. referenced as `${{ secrets.NAME }}`.
+/// Names consist of ASCII letters, digits and underscores and don't start with a digit.
+pub fn is_valid_secret_name(name: &str) -> bool {
+    let mut chars = name.chars();
+
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// A piece of an env value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EnvPart {
+    /// Text that is used as is.
+    Text(String),
+
+    /// A `${{ secrets.NAME }}` expression that is replaced with the value of the secret `NAME`.
+    Secret(String),
+}
+
+/// Splits an env value into text and secret references.
+pub fn parse_env_value(value: &str) -> Result<Vec<EnvPart>, SecretSyntaxError> {
+    let mut parts = Vec::new();
+    let mut rest = value;
+
+    while let Some(start) = rest.find(OPEN) {
+        if start > 0 {
+            parts.push(EnvPart::Text(rest[..start].to_string()));
+        }
+
+        let after_open = &rest[start + OPEN.len()..];
+        let Some(end) = after_open.find(CLOSE) else {
+            return Err(SecretSyntaxError::new(
+                &rest[start..],
+                "missing closing `}}`",
+            ));
+        };
+
+        let expression = &rest[start..start + OPEN.len() + end + CLOSE.len()];
+        let inner = after_open[..end].trim();
+
+        let Some(name) = inner.strip_prefix(PREFIX) else {
+            return Err(SecretSyntaxError::new(
+                expression,
+                "only `secrets.NAME` can be interpolated",
+            ));
+        };
+
+        if !is_valid_secret_name(name) {
+            return Err(SecretSyntaxError::new(
+                expression,
+                "secret names can only contain letters, digits and underscores and must not start with a digit",
+            ));
+        }
+
+        parts.push(EnvPart::Secret(name.to_string()));
+        rest = &after_open[end + CLOSE.len()..];
+    }
+
+    if !rest.is_empty() {
+        parts.push(EnvPart::Text(rest.to_string()));
+    }
+
+    Ok(parts)
+}
+
+/// The names of all secrets referenced in `env`, including those in nested tables and arrays.
+/// Every malformed expression is reported together with the key of the value it's in.
+pub fn secret_references(env: &Table) -> Result<BTreeSet<String>, MalformedSecretReferences> {
+    let mut names = BTreeSet::new();
+    let mut errors = Vec::new();
+
+    for (key, value) in env {
+        collect_references(key.clone(), value, &mut names, &mut errors);
+    }
+
+    if errors.is_empty() {
+        Ok(names)
+    } else {
+        Err(MalformedSecretReferences { errors })
+    }
+}
+
+fn collect_references(
+    key: String,
+    value: &Value,
+    names: &mut BTreeSet<String>,
+    errors: &mut Vec<(String, SecretSyntaxError)>,
+) {
+    match value {
+        Value::String(value) => match parse_env_value(value) {
+            Ok(parts) => names.extend(parts.into_iter().filter_map(|part| match part {
+                EnvPart::Secret(name) => Some(name),
+                EnvPart::Text(_) => None,
+            })),
+            Err(err) => errors.push((key, err)),
+        },
+        Value::Table(table) => {
+            for (nested, value) in table {
+                collect_references(format!("{key}.{nested}"), value, names, errors);
+            }
+        }
+        Value::Array(values) => {
+            for (index, value) in values.iter().enumerate() {
+                collect_references(format!("{key}[{index}]"), value, names, errors);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// A `${{ ... }}` expression that isn't a valid secret reference.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SecretSyntaxError {
+    /// The malformed expression, or the rest of the value if it isn't closed.
+    pub expression: String,
+    pub reason: &'static str,
+}
+
+impl SecretSyntaxError {
+    fn new(expression: &str, reason: &'static str) -> Self {
+        Self {
+            expression: expression.to_string(),
+            reason,
+        }
+    }
+}
+
+impl std::fmt::Display for SecretSyntaxError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "invalid secret reference `{}`: {}",
+            self.expression, self.reason
+        )
+    }
+}
+
+impl std::error::Error for SecretSyntaxError {}
+
+/// All malformed secret references of an `[env]` section by key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MalformedSecretReferences {
+    pub errors: Vec<(String, SecretSyntaxError)>,
+}
+
+impl std::fmt::Display for MalformedSecretReferences {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Malformed secret references in [env]:")?;
+        for (key, err) in &self.errors {
+            write!(f, "\n  {key}: {err}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for MalformedSecretReferences {}
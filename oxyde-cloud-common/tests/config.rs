@@ -0,0 +1,55 @@
+use oxyde_cloud_common::config::{CloudConfig, DomainsConfig};
+
+#[test]
+fn deployed_url_uses_verified_primary_domain() {
+    let mut config: CloudConfig = toml::from_str(
+        r#"
+        [app]
+        slug = "my-app"
+
+        [env]
+
+        [domains]
+        names = ["example.com", "www.example.com"]
+        primary = "www.example.com"
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(config.domains.primary(), Some("www.example.com"));
+    assert_eq!(config.deployed_url(), "https://my-app.oxydecloud.com");
+
+    config.verified_domains.insert("example.com".to_string());
+    assert_eq!(config.deployed_url(), "https://my-app.oxydecloud.com");
+
+    config
+        .verified_domains
+        .insert("www.example.com".to_string());
+    assert_eq!(config.deployed_url(), "https://www.example.com");
+}
+
+#[test]
+fn domains_are_optional() {
+    let config: CloudConfig = toml::from_str(
+        r#"
+        [app]
+        slug = "my-app"
+
+        [env]
+        "#,
+    )
+    .unwrap();
+
+    assert!(config.domains.is_empty());
+    assert_eq!(config.domains.primary(), None);
+}
+
+#[test]
+fn validates_domain_names() {
+    assert!(DomainsConfig::is_valid_domain("example.com"));
+    assert!(DomainsConfig::is_valid_domain("my-app.example.co.uk"));
+    assert!(!DomainsConfig::is_valid_domain("localhost"));
+    assert!(!DomainsConfig::is_valid_domain("Example.com"));
+    assert!(!DomainsConfig::is_valid_domain("-bad.example.com"));
+    assert!(!DomainsConfig::is_valid_domain("example..com"));
+}
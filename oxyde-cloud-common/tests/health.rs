@@ -0,0 +1,57 @@
+use std::time::Duration;
+
+use oxyde_cloud_common::config::CloudConfig;
+use oxyde_cloud_common::health::HealthConfig;
+
+#[test]
+fn parses_health_section() {
+    let config: CloudConfig = toml::from_str(
+        r#"
+        [app]
+        slug = "my-app"
+
+        [env]
+
+        [health]
+        path = "/healthz"
+        interval = "30s"
+        "#,
+    )
+    .unwrap();
+
+    let health = config.health.unwrap();
+    assert_eq!(health.path, "/healthz");
+    assert_eq!(health.expected_status, 200);
+    assert_eq!(health.interval, Duration::from_secs(30));
+    assert_eq!(health.timeout, Duration::from_secs(5));
+    health.validate().unwrap();
+
+    let invalid = HealthConfig {
+        path: "healthz".to_string(),
+        ..HealthConfig::default()
+    };
+    assert!(invalid.validate().is_err());
+
+    let invalid = HealthConfig {
+        timeout: Duration::from_secs(60),
+        ..HealthConfig::default()
+    };
+    assert!(invalid.validate().is_err());
+}
+
+#[test]
+fn joins_base_url_and_path() {
+    let health = HealthConfig {
+        path: "/healthz".to_string(),
+        ..HealthConfig::default()
+    };
+
+    assert_eq!(
+        health.url("https://my-app.oxydecloud.com"),
+        "https://my-app.oxydecloud.com/healthz"
+    );
+    assert_eq!(
+        health.url("https://my-app.oxydecloud.com/"),
+        "https://my-app.oxydecloud.com/healthz"
+    );
+}
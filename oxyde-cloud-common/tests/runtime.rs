@@ -0,0 +1,91 @@
+use std::time::Duration;
+
+use oxyde_cloud_common::config::CloudConfig;
+use oxyde_cloud_common::duration::{format_duration, parse_duration};
+use oxyde_cloud_common::runtime::{CpuClass, Instances, Memory, RuntimeConfig};
+
+fn runtime(toml: &str) -> RuntimeConfig {
+    let config: CloudConfig = toml::from_str(&format!(
+        "[app]\nslug = \"my-app\"\n\n[env]\n\n[runtime]\n{toml}"
+    ))
+    .unwrap();
+
+    config.runtime.unwrap()
+}
+
+#[test]
+fn parses_runtime_section() {
+    let runtime = runtime(
+        r#"
+        instances = { min = 0, max = 3 }
+        memory = "2GB"
+        cpu = "dedicated"
+        regions = ["fra", "iad"]
+        sleep_when_idle = "15m"
+        "#,
+    );
+
+    assert_eq!(runtime.instances, Instances { min: 0, max: 3 });
+    assert_eq!(runtime.memory, Some(Memory { megabytes: 2048 }));
+    assert_eq!(runtime.cpu, CpuClass::Dedicated);
+    assert_eq!(runtime.regions, ["fra", "iad"]);
+    assert_eq!(runtime.sleep_when_idle, Some(Duration::from_secs(900)));
+    runtime.validate().unwrap();
+
+    let json = serde_json::to_value(&runtime).unwrap();
+    assert_eq!(json["memory"], "2GB");
+    assert_eq!(json["sleep_when_idle"], "15m");
+    assert_eq!(
+        serde_json::from_value::<RuntimeConfig>(json).unwrap(),
+        runtime
+    );
+}
+
+#[test]
+fn defaults_missing_settings() {
+    let runtime = runtime("memory = \"512mb\"");
+
+    assert_eq!(runtime.instances, Instances { min: 1, max: 1 });
+    assert_eq!(runtime.memory, Some(Memory { megabytes: 512 }));
+    assert_eq!(runtime.cpu, CpuClass::Shared);
+    assert!(runtime.regions.is_empty());
+    assert_eq!(runtime.sleep_when_idle, None);
+}
+
+#[test]
+fn rejects_invalid_runtime_settings() {
+    assert!(
+        runtime("instances = { min = 2, max = 1 }")
+            .validate()
+            .is_err()
+    );
+    assert!(runtime("instances = { max = 0 }").validate().is_err());
+    assert!(runtime("memory = \"64MB\"").validate().is_err());
+    assert!(runtime("regions = [\"fra\", \"fra\"]").validate().is_err());
+    assert!(runtime("sleep_when_idle = \"5m\"").validate().is_err());
+    assert!(
+        runtime("instances = { min = 0 }\nsleep_when_idle = \"5m\"")
+            .validate()
+            .is_ok()
+    );
+
+    let unknown = "[app]\nslug = \"my-app\"\n\n[env]\n\n[runtime]\nmemroy = \"1GB\"";
+    assert!(toml::from_str::<CloudConfig>(unknown).is_err());
+    let bad_memory = "[app]\nslug = \"my-app\"\n\n[env]\n\n[runtime]\nmemory = \"1TB\"";
+    assert!(toml::from_str::<CloudConfig>(bad_memory).is_err());
+}
+
+#[test]
+fn parses_and_formats_durations() {
+    assert_eq!(parse_duration("30s"), Some(Duration::from_secs(30)));
+    assert_eq!(parse_duration("2h"), Some(Duration::from_secs(7200)));
+    assert_eq!(parse_duration("1d"), Some(Duration::from_secs(86400)));
+    assert_eq!(parse_duration("5"), None);
+    assert_eq!(parse_duration("m"), None);
+    assert_eq!(parse_duration("5w"), None);
+
+    assert_eq!(format_duration(Duration::from_secs(90)), "90s");
+    assert_eq!(format_duration(Duration::from_secs(900)), "15m");
+    assert_eq!(format_duration(Duration::from_secs(86400)), "1d");
+    assert_eq!(format_duration(Duration::ZERO), "0s");
+}
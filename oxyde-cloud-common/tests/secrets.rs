@@ -0,0 +1,77 @@
+use oxyde_cloud_common::secrets::{EnvPart, parse_env_value, secret_references};
+
+#[test]
+fn parses_secret_references() {
+    let parts =
+        parse_env_value("postgresql://${{ secrets.DB_CREDENTIALS }}@${{secrets.HOST}}/db").unwrap();
+
+    assert_eq!(
+        parts,
+        [
+            EnvPart::Text("postgresql://".to_string()),
+            EnvPart::Secret("DB_CREDENTIALS".to_string()),
+            EnvPart::Text("@".to_string()),
+            EnvPart::Secret("HOST".to_string()),
+            EnvPart::Text("/db".to_string()),
+        ]
+    );
+
+    assert_eq!(
+        parse_env_value("costs $5 {{ not a secret }}").unwrap(),
+        [EnvPart::Text("costs $5 {{ not a secret }}".to_string())]
+    );
+    assert!(parse_env_value("").unwrap().is_empty());
+}
+
+#[test]
+fn rejects_malformed_references() {
+    let err = parse_env_value("${{ secrets.DATABASE_URL }").unwrap_err();
+    assert_eq!(err.expression, "${{ secrets.DATABASE_URL }");
+
+    let err = parse_env_value("x${{ env.HOME }}y").unwrap_err();
+    assert_eq!(err.expression, "${{ env.HOME }}");
+
+    assert!(parse_env_value("${{ secrets.1ST }}").is_err());
+    assert!(parse_env_value("${{ secrets.MY-SECRET }}").is_err());
+    assert!(parse_env_value("${{ secrets. }}").is_err());
+}
+
+#[test]
+fn collects_references_with_keys() {
+    let env: toml::Table = toml::from_str(
+        r#"
+        DATABASE_URL = "postgresql://${{ secrets.DB }}@host/db"
+        PORT = 3000
+        OTHER = "${{ secrets.DB }} ${{ secrets.TOKEN }}"
+
+        [nested]
+        list = ["${{ secrets.NESTED }}"]
+        "#,
+    )
+    .unwrap();
+
+    let names = secret_references(&env).unwrap();
+    assert_eq!(
+        names.into_iter().collect::<Vec<_>>(),
+        ["DB", "NESTED", "TOKEN"]
+    );
+
+    let env: toml::Table = toml::from_str(
+        r#"
+        GOOD = "${{ secrets.DB }}"
+        BAD = "${{ secrets.DATABSE-URL }}"
+
+        [nested]
+        list = ["ok", "${{ secret.X }}"]
+        "#,
+    )
+    .unwrap();
+
+    let err = secret_references(&env).unwrap_err();
+    let keys = err
+        .errors
+        .iter()
+        .map(|(key, _)| key.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(keys, ["BAD", "nested.list[1]"]);
+}
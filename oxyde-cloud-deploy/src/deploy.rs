@@ -1,82 +1,372 @@
+use crate::manifest::{DeployFile, manifest_path};
+use crate::progress::{FileUploadProgress, UploadTotals};
+use crate::{DeployEvent, DeployOptions};
 use anyhow::{Context, Result};
 use cargo_leptos::config::Opts;
-use oxyde_cloud_client::Client;
+use oxyde_cloud_client::{Client, ClientConfig, ClientError, UploadOptions, UploadProgress};
 use oxyde_cloud_common::config::CloudConfig;
+use oxyde_cloud_common::net::{DeployManifest, DomainStatus, LogEntry, ManifestResponse};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fmt::Write;
 use std::fs::read_dir;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::AtomicU64;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
 use walkdir::WalkDir;
 
-pub async fn deploy_with_config_file(config: &PathBuf, cargo_leptos_opts: Opts) -> Result<()> {
+/// Remembers open upload sessions so an interrupted deploy can resume its uploads. Relative to
+/// the target dir.
+const UPLOAD_STATE_FILE: &str = "oxyde-cloud/upload-state.json";
+
+pub async fn deploy_with_config_file(
+    config: &PathBuf,
+    cargo_leptos_opts: Opts,
+    options: &DeployOptions,
+) -> Result<()> {
     let config = CloudConfig::load(config)
         .await
         .with_context(|| format!("Failed to load config file: {}", config.display()))?;
-    deploy(&config, cargo_leptos_opts)
+    deploy(&config, cargo_leptos_opts, options)
         .await
         .context("Failed to deploy with loaded config")?;
     Ok(())
 }
 
-pub async fn deploy(config: &CloudConfig, cargo_leptos_opts: Opts) -> Result<()> {
+pub async fn deploy(
+    config: &CloudConfig,
+    cargo_leptos_opts: Opts,
+    options: &DeployOptions,
+) -> Result<()> {
+    let api_key = std::env::var("OXYDE_CLOUD_API_KEY")
+        .context("Environment variable OXYDE_CLOUD_API_KEY is required for deployment")?;
+    let client = ClientConfig::from_env()
+        .user_agent(format!(
+            "oxyde-cloud-deploy/{} {}",
+            env!("CARGO_PKG_VERSION"),
+            ClientConfig::DEFAULT_USER_AGENT
+        ))
+        .build(api_key)
+        .context("Failed to create API client")?;
+
+    // Catch missing secrets before spending minutes on the build.
+    check_secrets(config, &client).await?;
+
+    let config = &CloudConfig {
+        verified_domains: verified_domains(config, &client).await?,
+        ..config.clone()
+    };
+
+    options.emit(DeployEvent::Building);
+
     crate::build::build(cargo_leptos_opts.clone())
         .await
         .context("Failed to build project")?;
 
-    let target_dir = "target";
-    let target_bin_dir = std::env::var("OXYDE_CLOUD_BIN_DIR").unwrap_or_else(|_| "target/x86_64-unknown-linux-musl".to_string());
+    let target_bin_dir = std::env::var("OXYDE_CLOUD_BIN_DIR")
+        .unwrap_or_else(|_| "target/x86_64-unknown-linux-musl".to_string());
 
     let server_bin_dir = if cargo_leptos_opts.release {
         "release"
     } else {
         "debug"
     };
-    let frontend_dir = "site";
+    let server_path = Path::new(&target_bin_dir).join(server_bin_dir);
 
-    let api_key = std::env::var("OXYDE_CLOUD_API_KEY")
-        .context("Environment variable OXYDE_CLOUD_API_KEY is required for deployment")?;
-    let client = Client::new(api_key.clone());
+    deploy_build_output(config, &client, Path::new("target"), &server_path, options).await
+}
 
-    let frontend_path = Path::new(target_dir).join(frontend_dir);
-    let server_path = Path::new(&target_bin_dir).join(server_bin_dir);
+/// Deploys what a build left behind: the frontend in the `site` dir of `target_dir` and the
+/// server binaries in `server_dir`. Unlike [`deploy`], this neither builds the project nor checks
+/// the secrets and domains of `config`.
+pub async fn deploy_build_output(
+    config: &CloudConfig,
+    client: &Client,
+    target_dir: &Path,
+    server_dir: &Path,
+    options: &DeployOptions,
+) -> Result<()> {
+    let frontend_path = target_dir.join("site");
+
+    let mut paths = Vec::new();
+
+    for path in recursive_files_from_dir(&frontend_path) {
+        let relative = path
+            .strip_prefix(target_dir)
+            .expect("frontend files are inside the target dir");
+        let manifest_path = manifest_path(relative);
+        paths.push((path, manifest_path, false));
+    }
+
+    for path in server_files(server_dir).context("Failed to collect server files")? {
+        let file_name = path
+            .file_name()
+            .expect("server files have a file name")
+            .to_string_lossy()
+            .to_string();
+        paths.push((path, file_name, true));
+    }
+
+    options.emit(DeployEvent::Hashing {
+        file_count: paths.len(),
+    });
 
-    let mut files = recursive_files_from_dir(frontend_path);
-    files.append(&mut server_files(server_path).context("Failed to collect server files")?);
+    let mut files = Vec::with_capacity(paths.len());
+    for (path, manifest_path, executable) in paths {
+        files.push(DeployFile::new(path, manifest_path, executable).await?);
+    }
 
     log::debug!(target:"cargo_leptos", "Found files: {:#?}", files);
 
     log::info!(target:"cargo_leptos", "Deploying app {}", config.app.slug);
 
-    if let Err(err) = deploy_inner(config, client, &mut files).await {
+    let upload_state_file = target_dir.join(UPLOAD_STATE_FILE);
+    if let Err(err) = deploy_inner(config, client, files, &upload_state_file, options).await {
         log::error!(target:"cargo_leptos", "Deploy failed: {:?}", err);
         return Err(err);
     }
 
+    if let Some(timeout) = options.health_probe_timeout {
+        let health = config.health.clone().unwrap_or_default();
+        let url = health.url(&config.deployed_url());
+
+        options.emit(DeployEvent::CheckingHealth { url: url.clone() });
+        crate::probe_health(&url, &health, timeout)
+            .await
+            .context("The deployed app isn't healthy")?;
+    }
+
     log::info!(target:"cargo_leptos", "Deployed app to {}", config.deployed_url());
+    options.emit(DeployEvent::Deployed {
+        url: config.deployed_url(),
+    });
 
     Ok(())
 }
 
 async fn deploy_inner(
     config: &CloudConfig,
-    client: Client,
-    files: &mut Vec<PathBuf>,
+    client: &Client,
+    files: Vec<DeployFile>,
+    upload_state_file: &Path,
+    options: &DeployOptions,
 ) -> Result<()> {
-    for file in files {
-        let file_path = file.display().to_string();
-        log::debug!(target:"cargo_leptos", "Uploading {}...", file_path);
-        client
-            .clone()
-            .upload_file(&config.app.slug, file)
-            .await
-            .with_context(|| format!("Failed to upload file: {file_path}"))?;
+    let manifest = DeployManifest {
+        app_slug: config.app.slug.clone(),
+        files: files.iter().map(|file| file.entry.clone()).collect(),
+    };
+
+    let ManifestResponse { missing_blobs } = client
+        .submit_manifest(&manifest)
+        .await
+        .context("Failed to submit deploy manifest")?;
+    let missing_blobs = missing_blobs.into_iter().collect::<HashSet<_>>();
+
+    // Files with identical contents only have to be uploaded once.
+    let mut blobs = BTreeMap::new();
+    for file in &files {
+        if missing_blobs.contains(&file.entry.sha256) {
+            blobs
+                .entry(file.entry.sha256.clone())
+                .or_insert_with(|| (file.local_path.clone(), file.entry.size));
+        }
     }
 
+    let unchanged = files
+        .iter()
+        .filter(|file| !missing_blobs.contains(&file.entry.sha256))
+        .count();
+    log::info!(
+        target:"cargo_leptos",
+        "{unchanged} of {} files unchanged, uploading {} blobs",
+        files.len(),
+        blobs.len()
+    );
+
+    options.emit(DeployEvent::UploadStarted {
+        file_count: blobs.len(),
+        unchanged_count: unchanged,
+        total_bytes: blobs.values().map(|(_, size)| size).sum(),
+    });
+
+    upload_blobs(config, client, blobs, upload_state_file, options).await?;
+
     log::debug!(target:"cargo_leptos", "Deploying app...");
-    client
+    options.emit(DeployEvent::Finalizing);
+    let deployment_id = client
         .upload_done(config)
         .await
         .context("Failed to signal deployment completion")?;
 
-    Ok(())
+    options.emit(DeployEvent::Starting {
+        deployment_id: deployment_id.clone(),
+    });
+    match client
+        .wait_for_deployment(&deployment_id, options.deployment_timeout)
+        .await
+    {
+        Ok(_) => Ok(()),
+        Err(ClientError::DeploymentFailed {
+            deployment,
+            startup_log,
+        }) => Err(deployment_failed(&deployment.id, &startup_log)),
+        Err(err) => Err(err).context("Failed to confirm that the deployment is live"),
+    }
+}
+
+/// Fails if `[env]` contains malformed secret references or references secrets the app can't
+/// access.
+async fn check_secrets(config: &CloudConfig, client: &Client) -> Result<()> {
+    let names = config.secret_references()?;
+    if names.is_empty() {
+        return Ok(());
+    }
+
+    let names = names.into_iter().collect::<Vec<_>>();
+    let missing = client
+        .missing_secrets(&config.app.slug, &names)
+        .await
+        .context("Failed to check the secrets referenced in [env]")?;
+
+    if missing.is_empty() {
+        return Ok(());
+    }
+
+    let mut message = format!(
+        "App '{}' references secrets that don't exist:",
+        config.app.slug
+    );
+    for name in &missing {
+        let _ = write!(message, "\n  {name}");
+    }
+    message.push_str("\nCreate them with `oxy secrets set <NAME>` before deploying.");
+
+    Err(anyhow::anyhow!(message))
+}
+
+/// Returns the domains of `[domains]` that are verified. Domains are only ever added with
+/// `oxy domains add`, so the ones the app doesn't have yet are merely pointed out.
+async fn verified_domains(config: &CloudConfig, client: &Client) -> Result<BTreeSet<String>> {
+    if config.domains.names.is_empty() {
+        return Ok(BTreeSet::new());
+    }
+
+    let domains = client
+        .domains(&config.app.slug)
+        .await
+        .context("Failed to fetch the domains of the app")?;
+
+    for name in &config.domains.names {
+        if !domains.iter().any(|domain| &domain.name == name) {
+            log::warn!(
+                target:"cargo_leptos",
+                "Domain {name} isn't set up for this app. Add it with `oxy domains add {name}`."
+            );
+        }
+    }
+
+    Ok(domains
+        .into_iter()
+        .filter(|domain| domain.status == DomainStatus::Verified)
+        .map(|domain| domain.name)
+        .collect())
+}
+
+fn deployment_failed(deployment_id: &str, startup_log: &[LogEntry]) -> anyhow::Error {
+    let mut message = format!("Deployment {deployment_id} failed to start");
+
+    if !startup_log.is_empty() {
+        message.push_str(". Last log lines:");
+        for entry in startup_log {
+            let _ = write!(message, "\n  {}", entry.message);
+        }
+    }
+
+    anyhow::anyhow!(message)
+}
+
+/// Uploads up to `upload_concurrency` blobs at the same time. As soon as one upload fails, all
+/// other uploads are aborted and every file that failed is reported.
+async fn upload_blobs(
+    config: &CloudConfig,
+    client: &Client,
+    blobs: BTreeMap<String, (PathBuf, u64)>,
+    upload_state_file: &Path,
+    options: &DeployOptions,
+) -> Result<()> {
+    let semaphore = Arc::new(Semaphore::new(options.upload_concurrency.max(1)));
+    let total = blobs.len();
+
+    let totals = options.progress.clone().map(|progress| {
+        Arc::new(UploadTotals {
+            file_count: total,
+            total_bytes: blobs.values().map(|(_, size)| size).sum(),
+            bytes_sent: AtomicU64::new(0),
+            progress,
+        })
+    });
+
+    let mut uploads = JoinSet::new();
+
+    for (file_index, (sha256, (file, _))) in blobs.into_iter().enumerate() {
+        let client = client.clone();
+        let app_slug = config.app.slug.clone();
+        let semaphore = Arc::clone(&semaphore);
+
+        let mut upload_options = UploadOptions::with_state_file(upload_state_file);
+        upload_options.progress = totals.clone().map(|totals| {
+            Arc::new(FileUploadProgress {
+                file_index,
+                file_bytes_sent: AtomicU64::new(0),
+                totals,
+            }) as Arc<dyn UploadProgress>
+        });
+
+        uploads.spawn(async move {
+            let _permit = semaphore
+                .acquire_owned()
+                .await
+                .expect("semaphore is never closed");
+
+            log::debug!(target:"cargo_leptos", "Uploading {}...", file.display());
+            let result = client
+                .upload_blob_with_options(&app_slug, &file, &sha256, &upload_options)
+                .await;
+
+            (file, result)
+        });
+    }
+
+    let mut failed = Vec::new();
+    let mut aborted = 0;
+
+    while let Some(joined) = uploads.join_next().await {
+        match joined {
+            Ok((_, Ok(()))) => {}
+            Ok((file, Err(err))) => {
+                uploads.abort_all();
+                failed.push((file, anyhow::Error::from(err)));
+            }
+            Err(err) if err.is_cancelled() => aborted += 1,
+            Err(err) => std::panic::resume_unwind(err.into_panic()),
+        }
+    }
+
+    if failed.is_empty() {
+        return Ok(());
+    }
+
+    let mut message = format!("Failed to upload {} of {total} files", failed.len());
+    if aborted > 0 {
+        let _ = write!(message, " ({aborted} aborted)");
+    }
+    message.push(':');
+    for (file, err) in &failed {
+        let _ = write!(message, "\n  {}: {err:#}", file.display());
+    }
+
+    Err(anyhow::anyhow!(message))
 }
 
 fn recursive_files_from_dir(dir: impl AsRef<Path>) -> Vec<PathBuf> {
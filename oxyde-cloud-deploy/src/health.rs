@@ -0,0 +1,39 @@
+use anyhow::{Context, Result, bail};
+use oxyde_cloud_common::duration::format_duration;
+use oxyde_cloud_common::health::HealthConfig;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Requests `url`, usually built with [`HealthConfig::url`], every `health.interval` until it
+/// responds with `health.expected_status`. Fails with the last response or error if that doesn't
+/// happen within `timeout`.
+pub async fn probe_health(url: &str, health: &HealthConfig, timeout: Duration) -> Result<()> {
+    let client = reqwest::Client::builder()
+        .timeout(health.timeout)
+        .build()
+        .context("Failed to create HTTP client for the health check")?;
+
+    let deadline = Instant::now() + timeout;
+
+    loop {
+        let last_result = match client.get(url).send().await {
+            Ok(res) if res.status().as_u16() == health.expected_status => return Ok(()),
+            Ok(res) => format!("responded with {}", res.status()),
+            Err(err) if err.is_timeout() => {
+                format!("didn't respond within {}", format_duration(health.timeout))
+            }
+            Err(err) => format!("failed: {err}"),
+        };
+
+        let now = Instant::now();
+        if now >= deadline {
+            bail!(
+                "Health check of {url} didn't pass within {}. The last check {last_result}",
+                format_duration(timeout)
+            );
+        }
+
+        log::debug!(target:"cargo_leptos", "Health check of {url} {last_result}");
+        tokio::time::sleep(health.interval.min(deadline - now)).await;
+    }
+}
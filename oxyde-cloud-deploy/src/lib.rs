@@ -1,5 +1,13 @@
 mod build;
 mod deploy;
+mod health;
+mod manifest;
+mod options;
+mod progress;
 
 pub use cargo_leptos::config::{Cli, Opts};
-pub use deploy::deploy_with_config_file;
+pub use deploy::{deploy, deploy_build_output, deploy_with_config_file};
+pub use health::probe_health;
+pub use options::DeployOptions;
+pub use oxyde_cloud_client::UploadEvent;
+pub use progress::{DeployEvent, DeployProgress};
@@ -0,0 +1,51 @@
+use anyhow::{Context, Result};
+use oxyde_cloud_client::sha256_file;
+use oxyde_cloud_common::net::ManifestEntry;
+use std::path::{Component, Path, PathBuf};
+
+/// A local file that is part of a deployment together with its manifest entry.
+#[derive(Debug, Clone)]
+pub(crate) struct DeployFile {
+    pub local_path: PathBuf,
+    pub entry: ManifestEntry,
+}
+
+impl DeployFile {
+    /// Hashes the file at `local_path`. `manifest_path` is where the file ends up relative to
+    /// the deployment root.
+    pub async fn new(local_path: PathBuf, manifest_path: String, executable: bool) -> Result<Self> {
+        let metadata = std::fs::metadata(&local_path)
+            .with_context(|| format!("Failed to read metadata of {}", local_path.display()))?;
+        let sha256 = sha256_file(&local_path)
+            .await
+            .with_context(|| format!("Failed to hash {}", local_path.display()))?;
+
+        Ok(Self {
+            entry: ManifestEntry {
+                path: manifest_path,
+                sha256,
+                size: metadata.len(),
+                mode: mode(executable),
+            },
+            local_path,
+        })
+    }
+}
+
+/// Joins the components of a relative path with `/` regardless of the platform.
+pub(crate) fn manifest_path(relative: &Path) -> String {
+    relative
+        .components()
+        .filter_map(|c| match c {
+            Component::Normal(c) => Some(c.to_string_lossy()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// The permissions a file is deployed with. They only depend on whether the file is executable,
+/// so the manifest is the same no matter which platform or umask the project was built with.
+fn mode(executable: bool) -> u32 {
+    if executable { 0o755 } else { 0o644 }
+}
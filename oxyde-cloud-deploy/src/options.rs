@@ -0,0 +1,81 @@
+use crate::{DeployEvent, DeployProgress};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Settings that control how a deploy is carried out, as opposed to what is deployed which is
+/// configured in `oxyde-cloud.toml`.
+#[derive(Clone)]
+pub struct DeployOptions {
+    /// How many files are uploaded at the same time. All uploads share the same connection pool.
+    pub upload_concurrency: usize,
+
+    /// How long to wait for the new version to go live after uploading it.
+    pub deployment_timeout: Duration,
+
+    /// Probe the `[health]` path of the live app after deploying for up to this long with
+    /// [`probe_health`](crate::probe_health). `None` skips the probe.
+    pub health_probe_timeout: Option<Duration>,
+
+    /// Receives a [`DeployEvent`] whenever the deploy makes progress.
+    pub progress: Option<Arc<dyn DeployProgress>>,
+}
+
+impl Default for DeployOptions {
+    fn default() -> Self {
+        Self {
+            upload_concurrency: 4,
+            deployment_timeout: Duration::from_secs(300),
+            health_probe_timeout: None,
+            progress: None,
+        }
+    }
+}
+
+impl std::fmt::Debug for DeployOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DeployOptions")
+            .field("upload_concurrency", &self.upload_concurrency)
+            .field("deployment_timeout", &self.deployment_timeout)
+            .field("health_probe_timeout", &self.health_probe_timeout)
+            .field("progress", &self.progress.is_some())
+            .finish()
+    }
+}
+
+impl DeployOptions {
+    /// Default options overridden by the `OXYDE_CLOUD_UPLOAD_CONCURRENCY`,
+    /// `OXYDE_CLOUD_DEPLOYMENT_TIMEOUT` and `OXYDE_CLOUD_HEALTH_PROBE_TIMEOUT` (both in seconds)
+    /// environment variables.
+    pub fn from_env() -> Self {
+        let mut options = Self::default();
+
+        if let Some(upload_concurrency) = std::env::var("OXYDE_CLOUD_UPLOAD_CONCURRENCY")
+            .ok()
+            .and_then(|v| v.parse().ok())
+        {
+            options.upload_concurrency = upload_concurrency;
+        }
+
+        if let Some(seconds) = std::env::var("OXYDE_CLOUD_DEPLOYMENT_TIMEOUT")
+            .ok()
+            .and_then(|v| v.parse().ok())
+        {
+            options.deployment_timeout = Duration::from_secs(seconds);
+        }
+
+        if let Some(seconds) = std::env::var("OXYDE_CLOUD_HEALTH_PROBE_TIMEOUT")
+            .ok()
+            .and_then(|v| v.parse().ok())
+        {
+            options.health_probe_timeout = Some(Duration::from_secs(seconds));
+        }
+
+        options
+    }
+
+    pub(crate) fn emit(&self, event: DeployEvent) {
+        if let Some(progress) = &self.progress {
+            progress.on_event(event);
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use oxyde_cloud_client::{UploadEvent, UploadProgress};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Something that happened during a deploy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeployEvent {
+    /// The project is being built with cargo-leptos.
+    Building,
+
+    /// The built files are being hashed to find out which ones changed.
+    Hashing { file_count: usize },
+
+    /// The upload of everything the server doesn't have yet started.
+    UploadStarted {
+        file_count: usize,
+        unchanged_count: usize,
+        total_bytes: u64,
+    },
+
+    /// Progress of a single file upload. `bytes_sent` and `total_bytes` are summed up over all
+    /// files of this deploy.
+    Upload {
+        file_index: usize,
+        file_count: usize,
+        bytes_sent: u64,
+        total_bytes: u64,
+        event: UploadEvent,
+    },
+
+    /// All files are uploaded and the server is told to deploy them.
+    Finalizing,
+
+    /// The server started the deployment and it's waited for the new version to go live.
+    Starting { deployment_id: String },
+
+    /// The new version is live and its health check is probed.
+    CheckingHealth { url: String },
+
+    /// The new version of the app is live.
+    Deployed { url: String },
+}
+
+/// Receives [`DeployEvent`]s. Set it on [`DeployOptions::progress`](crate::DeployOptions).
+/// Implemented for closures.
+pub trait DeployProgress: Send + Sync {
+    fn on_event(&self, event: DeployEvent);
+}
+
+impl<F> DeployProgress for F
+where
+    F: Fn(DeployEvent) + Send + Sync,
+{
+    fn on_event(&self, event: DeployEvent) {
+        self(event)
+    }
+}
+
+/// Bytes sent over all concurrent uploads of a deploy.
+pub(crate) struct UploadTotals {
+    pub file_count: usize,
+    pub total_bytes: u64,
+    pub bytes_sent: AtomicU64,
+    pub progress: Arc<dyn DeployProgress>,
+}
+
+/// Forwards the events of one file upload as [`DeployEvent::Upload`].
+pub(crate) struct FileUploadProgress {
+    pub file_index: usize,
+    pub file_bytes_sent: AtomicU64,
+    pub totals: Arc<UploadTotals>,
+}
+
+impl UploadProgress for FileUploadProgress {
+    fn on_event(&self, event: UploadEvent) {
+        let file_bytes_sent = match &event {
+            UploadEvent::Started { bytes_sent, .. } | UploadEvent::Progress { bytes_sent, .. } => {
+                Some(*bytes_sent)
+            }
+            UploadEvent::Finished { total_bytes, .. } => Some(*total_bytes),
+            UploadEvent::ChunkUploaded { .. } => None,
+        };
+
+        let bytes_sent = match file_bytes_sent {
+            Some(new) => {
+                let old = self.file_bytes_sent.swap(new, Ordering::Relaxed);
+                if new >= old {
+                    self.totals
+                        .bytes_sent
+                        .fetch_add(new - old, Ordering::Relaxed)
+                        + (new - old)
+                } else {
+                    self.totals
+                        .bytes_sent
+                        .fetch_sub(old - new, Ordering::Relaxed)
+                        - (old - new)
+                }
+            }
+            None => self.totals.bytes_sent.load(Ordering::Relaxed),
+        };
+
+        self.totals.progress.on_event(DeployEvent::Upload {
+            file_index: self.file_index,
+            file_count: self.totals.file_count,
+            bytes_sent,
+            total_bytes: self.totals.total_bytes,
+            event,
+        });
+    }
+}
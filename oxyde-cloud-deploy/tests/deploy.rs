@@ -0,0 +1,385 @@
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::Result;
+use oxyde_cloud_common::net::{DeploymentStatus, ManifestEntry};
+use oxyde_cloud_deploy::{DeployEvent, DeployOptions, UploadEvent, deploy_build_output};
+use oxyde_cloud_testkit::fixtures::{cloud_config, manifest_entry};
+use oxyde_cloud_testkit::{FakeCloud, StatusCode};
+use tempfile::TempDir;
+
+/// A project as a release build of cargo-leptos leaves it behind.
+struct Project {
+    dir: TempDir,
+}
+
+impl Project {
+    fn new() -> Self {
+        let project = Self {
+            dir: tempfile::tempdir().unwrap(),
+        };
+
+        project.write("target/site/index.html", b"<html></html>");
+        project.write("target/site/pkg/app.wasm", b"wasm");
+        project.write("target/site/pkg/app.js", b"js");
+        project.write("target/release/my-app", b"server binary");
+        // Build artifacts next to the server binary that aren't deployed.
+        project.write("target/release/my-app.d", b"dependencies");
+        project.write("target/release/.cargo-lock", b"");
+        project.write("target/release/build/output", b"build script output");
+
+        project
+    }
+
+    fn write(&self, path: &str, contents: &[u8]) {
+        let path = self.dir.path().join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    }
+
+    /// Changes the permissions of a file where the platform has them.
+    fn set_mode(&self, path: &str, mode: u32) {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+
+            let permissions = std::fs::Permissions::from_mode(mode);
+            std::fs::set_permissions(self.dir.path().join(path), permissions).unwrap();
+        }
+        #[cfg(not(unix))]
+        let _ = (path, mode);
+    }
+
+    fn target_dir(&self) -> PathBuf {
+        self.dir.path().join("target")
+    }
+
+    async fn deploy(&self, cloud: &FakeCloud, options: &DeployOptions) -> Result<()> {
+        deploy_build_output(
+            &cloud_config("my-app"),
+            &cloud.client(),
+            &self.target_dir(),
+            &self.target_dir().join("release"),
+            options,
+        )
+        .await
+    }
+}
+
+/// Options that record every [`DeployEvent`] in the returned list.
+fn recording_options() -> (DeployOptions, Arc<Mutex<Vec<DeployEvent>>>) {
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let options = DeployOptions {
+        progress: Some(Arc::new({
+            let events = Arc::clone(&events);
+            move |event| events.lock().unwrap().push(event)
+        })),
+        ..DeployOptions::default()
+    };
+
+    (options, events)
+}
+
+fn manifest_paths(cloud: &FakeCloud) -> Vec<String> {
+    let mut paths = cloud.state().manifests["my-app"]
+        .files
+        .iter()
+        .map(|entry| entry.path.clone())
+        .collect::<Vec<_>>();
+    paths.sort();
+    paths
+}
+
+#[tokio::test]
+async fn deploys_built_projects() {
+    let cloud = FakeCloud::start().await;
+    cloud.state().add_app("my-app", "my-team");
+    let project = Project::new();
+
+    project
+        .deploy(&cloud, &DeployOptions::default())
+        .await
+        .unwrap();
+
+    assert_eq!(
+        manifest_paths(&cloud),
+        [
+            "my-app",
+            "site/index.html",
+            "site/pkg/app.js",
+            "site/pkg/app.wasm"
+        ]
+    );
+
+    let state = cloud.state();
+    assert_eq!(state.blobs.len(), 4);
+    assert_eq!(state.hits("apps/upload-file"), 4);
+    assert_eq!(state.deployments.len(), 1);
+    assert_eq!(state.deployments[0].status, DeploymentStatus::Success);
+    assert!(state.sessions.is_empty());
+}
+
+#[tokio::test]
+async fn describes_every_file_in_the_manifest() {
+    let cloud = FakeCloud::start().await;
+    cloud.state().add_app("my-app", "my-team");
+    let project = Project::new();
+    project.set_mode("target/site/index.html", 0o600);
+    project.set_mode("target/release/my-app", 0o700);
+
+    project
+        .deploy(&cloud, &DeployOptions::default())
+        .await
+        .unwrap();
+
+    let mut files = cloud.state().manifests["my-app"].files.clone();
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+
+    // Frontend files keep their path below the target dir, server binaries only their name.
+    // The mode only depends on whether a file is a server binary.
+    assert_eq!(
+        files,
+        [
+            ManifestEntry {
+                mode: 0o755,
+                ..manifest_entry("my-app", b"server binary")
+            },
+            manifest_entry("site/index.html", b"<html></html>"),
+            manifest_entry("site/pkg/app.js", b"js"),
+            manifest_entry("site/pkg/app.wasm", b"wasm"),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn uploads_only_changed_files_on_redeploy() {
+    let cloud = FakeCloud::start().await;
+    cloud.state().add_app("my-app", "my-team");
+    let project = Project::new();
+    let options = DeployOptions::default();
+
+    project.deploy(&cloud, &options).await.unwrap();
+    project.write("target/site/pkg/app.wasm", b"changed wasm");
+    project.deploy(&cloud, &options).await.unwrap();
+
+    let state = cloud.state();
+    assert_eq!(state.hits("apps/upload-file"), 5);
+    assert_eq!(state.blobs.len(), 5);
+    assert_eq!(state.deployments.len(), 2);
+}
+
+#[tokio::test]
+async fn uploads_identical_files_once() {
+    let cloud = FakeCloud::start().await;
+    cloud.state().add_app("my-app", "my-team");
+    let project = Project::new();
+    project.write("target/site/copy.html", b"<html></html>");
+
+    project
+        .deploy(&cloud, &DeployOptions::default())
+        .await
+        .unwrap();
+
+    let state = cloud.state();
+    assert_eq!(state.manifests["my-app"].files.len(), 5);
+    assert_eq!(state.hits("apps/upload-file"), 4);
+}
+
+#[tokio::test]
+async fn reports_failed_deployments() {
+    let cloud = FakeCloud::start().await;
+    {
+        let mut state = cloud.state();
+        state.add_app("my-app", "my-team");
+        state.deployment_outcome = DeploymentStatus::Failure;
+    }
+    let project = Project::new();
+
+    let err = project
+        .deploy(&cloud, &DeployOptions::default())
+        .await
+        .unwrap_err();
+
+    assert!(format!("{err:#}").contains("failed to start"), "{err:#}");
+}
+
+#[tokio::test]
+async fn fails_for_unknown_apps() {
+    let cloud = FakeCloud::start().await;
+    let project = Project::new();
+
+    let err = project
+        .deploy(&cloud, &DeployOptions::default())
+        .await
+        .unwrap_err();
+
+    assert!(
+        format!("{err:#}").contains("Failed to submit deploy manifest"),
+        "{err:#}"
+    );
+    assert!(cloud.state().deployments.is_empty());
+}
+
+#[tokio::test]
+async fn aborts_other_uploads_after_the_first_failure() {
+    let cloud = FakeCloud::start().await;
+    cloud.state().add_app("my-app", "my-team");
+    {
+        // The latency keeps the next upload in flight when the first one fails.
+        let mut faults = cloud.faults();
+        faults.latency = Some(Duration::from_millis(100));
+        faults.fail_next("apps/blobs/new", StatusCode::BAD_REQUEST);
+    }
+    let project = Project::new();
+    let options = DeployOptions {
+        upload_concurrency: 1,
+        ..DeployOptions::default()
+    };
+
+    let err = project.deploy(&cloud, &options).await.unwrap_err();
+
+    let message = err.to_string();
+    let mut lines = message.lines();
+    assert_eq!(
+        lines.next(),
+        Some("Failed to upload 1 of 4 files (3 aborted):")
+    );
+    let failure = lines.next().unwrap();
+    assert!(failure.contains("Injected failure"), "{failure}");
+    assert_eq!(lines.next(), None);
+
+    let state = cloud.state();
+    assert_eq!(state.hits("apps/upload-file"), 0);
+    assert!(state.deployments.is_empty());
+}
+
+#[tokio::test]
+async fn reports_every_failed_upload() {
+    let cloud = FakeCloud::start().await;
+    cloud.state().add_app("my-app", "my-team");
+    cloud
+        .faults()
+        .fail_next_n("apps/blobs/new", StatusCode::BAD_REQUEST, 4);
+    let project = Project::new();
+
+    let err = project
+        .deploy(&cloud, &DeployOptions::default())
+        .await
+        .unwrap_err();
+
+    // All uploads fail at once, so how many of them finish before the rest is aborted varies.
+    let message = err.to_string();
+    let failures = message
+        .lines()
+        .skip(1)
+        .filter(|line| line.contains("Injected failure"))
+        .count();
+    assert!(failures >= 1, "{message}");
+    assert_eq!(message.lines().count(), failures + 1, "{message}");
+
+    let summary = match 4 - failures {
+        0 => format!("Failed to upload {failures} of 4 files:"),
+        aborted => format!("Failed to upload {failures} of 4 files ({aborted} aborted):"),
+    };
+    assert!(message.starts_with(&summary), "{message}");
+}
+
+#[tokio::test]
+async fn reports_the_progress_of_a_deploy() {
+    let cloud = FakeCloud::start().await;
+    cloud.state().add_app("my-app", "my-team");
+    let project = Project::new();
+    let (options, events) = recording_options();
+
+    project.deploy(&cloud, &options).await.unwrap();
+
+    let events = events.lock().unwrap().clone();
+    let (uploads, steps): (Vec<_>, Vec<_>) = events
+        .into_iter()
+        .partition(|event| matches!(event, DeployEvent::Upload { .. }));
+
+    let deployment_id = cloud.state().deployments[0].id.clone();
+    assert_eq!(
+        steps,
+        [
+            DeployEvent::Hashing { file_count: 4 },
+            DeployEvent::UploadStarted {
+                file_count: 4,
+                unchanged_count: 0,
+                total_bytes: 32,
+            },
+            DeployEvent::Finalizing,
+            DeployEvent::Starting { deployment_id },
+            DeployEvent::Deployed {
+                url: "https://my-app.oxydecloud.com".to_string(),
+            },
+        ]
+    );
+
+    // The bytes of all files add up to the total without ever going back.
+    let mut last_bytes_sent = 0;
+    let mut finished = BTreeSet::new();
+    for event in uploads {
+        let DeployEvent::Upload {
+            file_index,
+            file_count,
+            bytes_sent,
+            total_bytes,
+            event,
+        } = event
+        else {
+            unreachable!();
+        };
+
+        assert_eq!(file_count, 4);
+        assert_eq!(total_bytes, 32);
+        assert!(bytes_sent >= last_bytes_sent);
+        last_bytes_sent = bytes_sent;
+
+        if matches!(event, UploadEvent::Finished { .. }) {
+            finished.insert(file_index);
+        }
+    }
+    assert_eq!(last_bytes_sent, 32);
+    assert_eq!(finished, BTreeSet::from([0, 1, 2, 3]));
+}
+
+#[tokio::test]
+async fn reports_only_changed_files_as_uploads() {
+    let cloud = FakeCloud::start().await;
+    cloud.state().add_app("my-app", "my-team");
+    let project = Project::new();
+
+    project
+        .deploy(&cloud, &DeployOptions::default())
+        .await
+        .unwrap();
+    project.write("target/site/pkg/app.wasm", b"changed wasm");
+
+    let (options, events) = recording_options();
+    project.deploy(&cloud, &options).await.unwrap();
+
+    let events = events.lock().unwrap();
+    assert!(events.contains(&DeployEvent::UploadStarted {
+        file_count: 1,
+        unchanged_count: 3,
+        total_bytes: 12,
+    }));
+
+    let last_upload = events
+        .iter()
+        .rfind(|event| matches!(event, DeployEvent::Upload { .. }))
+        .unwrap();
+    let DeployEvent::Upload {
+        file_count,
+        bytes_sent,
+        total_bytes,
+        ..
+    } = last_upload
+    else {
+        unreachable!();
+    };
+    assert_eq!((*file_count, *bytes_sent, *total_bytes), (1, 12, 12));
+}
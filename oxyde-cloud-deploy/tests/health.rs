@@ -0,0 +1,93 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use axum::Router;
+use axum::http::StatusCode;
+use axum::routing::get;
+use oxyde_cloud_common::health::HealthConfig;
+use oxyde_cloud_deploy::probe_health;
+use tokio::net::TcpListener;
+
+/// Serves `/healthz` on a random local port and returns its URL. It fails the first `failures`
+/// requests and takes `delay` to answer each one.
+async fn stand_in(failures: usize, delay: Duration) -> (String, Arc<AtomicUsize>) {
+    let hits = Arc::new(AtomicUsize::new(0));
+
+    let app = Router::new().route(
+        "/healthz",
+        get({
+            let hits = Arc::clone(&hits);
+            move || async move {
+                tokio::time::sleep(delay).await;
+
+                if hits.fetch_add(1, Ordering::SeqCst) < failures {
+                    StatusCode::SERVICE_UNAVAILABLE
+                } else {
+                    StatusCode::OK
+                }
+            }
+        }),
+    );
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await });
+
+    (format!("http://{addr}/healthz"), hits)
+}
+
+fn health() -> HealthConfig {
+    HealthConfig {
+        path: "/healthz".to_string(),
+        interval: Duration::from_millis(20),
+        timeout: Duration::from_millis(200),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn passes_once_the_app_is_healthy() {
+    let (url, hits) = stand_in(3, Duration::ZERO).await;
+
+    probe_health(&url, &health(), Duration::from_secs(5))
+        .await
+        .unwrap();
+
+    assert_eq!(hits.load(Ordering::SeqCst), 4);
+}
+
+#[tokio::test]
+async fn fails_if_the_app_stays_unhealthy() {
+    let (url, _) = stand_in(usize::MAX, Duration::ZERO).await;
+
+    let err = probe_health(&url, &health(), Duration::from_millis(200))
+        .await
+        .unwrap_err();
+
+    assert!(err.to_string().contains("503"), "{err}");
+}
+
+#[tokio::test]
+async fn counts_slow_responses_as_failures() {
+    let (url, _) = stand_in(0, Duration::from_secs(2)).await;
+
+    let err = probe_health(&url, &health(), Duration::from_millis(300))
+        .await
+        .unwrap_err();
+
+    assert!(err.to_string().contains("didn't respond"), "{err}");
+}
+
+#[tokio::test]
+async fn expects_the_configured_status() {
+    let (url, _) = stand_in(usize::MAX, Duration::ZERO).await;
+
+    let health = HealthConfig {
+        expected_status: 503,
+        ..health()
+    };
+    probe_health(&url, &health, Duration::from_secs(5))
+        .await
+        .unwrap();
+}
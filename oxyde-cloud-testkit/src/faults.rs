@@ -0,0 +1,61 @@
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+
+use axum::http::StatusCode;
+
+/// Faults the fake API injects into its responses. Configure them through
+/// [`FakeCloud::faults`](crate::FakeCloud::faults).
+#[derive(Debug, Default)]
+pub struct Faults {
+    /// Delay before every request is handled.
+    pub latency: Option<Duration>,
+
+    failures: HashMap<String, VecDeque<StatusCode>>,
+    dropped_chunks: usize,
+    log_stream_limit: Option<usize>,
+}
+
+impl Faults {
+    /// Answers the next request to `route` with `status` instead of handling it. Calls add up,
+    /// so failing a route twice fails the next two requests to it.
+    pub fn fail_next(&mut self, route: &str, status: StatusCode) -> &mut Self {
+        self.fail_next_n(route, status, 1)
+    }
+
+    /// Answers the next `times` requests to `route` with `status`.
+    pub fn fail_next_n(&mut self, route: &str, status: StatusCode, times: usize) -> &mut Self {
+        let failures = self.failures.entry(route.to_string()).or_default();
+        failures.extend(std::iter::repeat_n(status, times));
+        self
+    }
+
+    /// Accepts the next `count` chunk uploads without storing them, as if they got lost on the
+    /// way. The affected uploads stay incomplete until the chunks are sent again.
+    pub fn drop_next_chunks(&mut self, count: usize) -> &mut Self {
+        self.dropped_chunks += count;
+        self
+    }
+
+    /// Ends every log stream after `lines` lines, so clients following a log have to reconnect.
+    pub fn end_log_streams_after(&mut self, lines: usize) -> &mut Self {
+        self.log_stream_limit = Some(lines);
+        self
+    }
+
+    pub(crate) fn log_stream_limit(&self) -> Option<usize> {
+        self.log_stream_limit
+    }
+
+    pub(crate) fn take_failure(&mut self, route: &str) -> Option<StatusCode> {
+        self.failures.get_mut(route)?.pop_front()
+    }
+
+    pub(crate) fn take_dropped_chunk(&mut self) -> bool {
+        if self.dropped_chunks > 0 {
+            self.dropped_chunks -= 1;
+            true
+        } else {
+            false
+        }
+    }
+}
@@ -0,0 +1,31 @@
+//! Values the tests of the client and the deploy pipeline build their requests from.
+
+use oxyde_cloud_common::config::{AppConfig, CloudConfig};
+use oxyde_cloud_common::net::ManifestEntry;
+
+use crate::sha256;
+
+/// A config for `app_slug` with every optional section left out.
+pub fn cloud_config(app_slug: &str) -> CloudConfig {
+    CloudConfig {
+        app: AppConfig {
+            slug: app_slug.to_string(),
+        },
+        env: Default::default(),
+        runtime: None,
+        health: None,
+        domains: Default::default(),
+        leptos_config: None,
+        verified_domains: Default::default(),
+    }
+}
+
+/// The manifest entry of a regular file at `path` with the given contents.
+pub fn manifest_entry(path: &str, contents: &[u8]) -> ManifestEntry {
+    ManifestEntry {
+        path: path.to_string(),
+        sha256: sha256(contents),
+        size: contents.len() as u64,
+        mode: 0o644,
+    }
+}
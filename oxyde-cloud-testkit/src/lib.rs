@@ -0,0 +1,174 @@
+//! An in-process fake of the Oxyde Cloud API for integration tests that run fully offline.
+//!
+//! [`FakeCloud::start`] serves every route the [`Client`] uses from memory on a random local
+//! port. Tests seed and inspect the stored data through [`FakeCloud::state`] and inject latency,
+//! error responses or lost upload chunks through [`FakeCloud::faults`].
+//!
+//! ```no_run
+//! # async fn example() -> oxyde_cloud_client::Result<()> {
+//! use oxyde_cloud_testkit::FakeCloud;
+//!
+//! let cloud = FakeCloud::start().await;
+//! cloud.state().add_team("my-team", "My Team");
+//!
+//! let client = cloud.client();
+//! assert!(client.new_app("my-app", "my-team", "My App").await?);
+//! assert_eq!(cloud.state().apps["my-app"], "my-team");
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Tests of the [`blocking::Client`](oxyde_cloud_client::blocking::Client) can't run inside a
+//! runtime, so they start the server with [`FakeCloud::start_blocking`] instead.
+
+mod faults;
+pub mod fixtures;
+mod routes;
+mod state;
+
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+use oxyde_cloud_client::{Client, ClientConfig, RetryPolicy};
+use sha2::{Digest, Sha256};
+use tokio::runtime::Runtime;
+use tokio::task::JoinHandle;
+
+pub use axum::http::StatusCode;
+pub use faults::Faults;
+pub use state::{CloudState, Session, SessionTarget, StoredSecret};
+
+/// The API key [`FakeCloud::start`] accepts.
+pub const DEFAULT_API_KEY: &str = "test-api-key";
+
+/// A running fake API server. It's shut down when dropped.
+pub struct FakeCloud {
+    addr: SocketAddr,
+    shared: Arc<Shared>,
+    server: JoinHandle<()>,
+    runtime: Option<Runtime>,
+}
+
+pub(crate) struct Shared {
+    api_key: String,
+    state: Mutex<CloudState>,
+    faults: Mutex<Faults>,
+}
+
+impl Shared {
+    pub(crate) fn state(&self) -> MutexGuard<'_, CloudState> {
+        self.state.lock().expect("state mutex poisoned")
+    }
+
+    pub(crate) fn faults(&self) -> MutexGuard<'_, Faults> {
+        self.faults.lock().expect("faults mutex poisoned")
+    }
+}
+
+impl FakeCloud {
+    /// Starts a server that accepts [`DEFAULT_API_KEY`]. Has to be called from within a Tokio
+    /// runtime which keeps serving requests as long as it runs.
+    pub async fn start() -> Self {
+        Self::start_with_api_key(DEFAULT_API_KEY).await
+    }
+
+    /// Starts a server on a runtime of its own, for tests that aren't async themselves. The
+    /// runtime is shut down together with the server.
+    pub fn start_blocking() -> Self {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .enable_all()
+            .build()
+            .expect("Failed to start fake API runtime");
+
+        let mut cloud = runtime.block_on(Self::start());
+        cloud.runtime = Some(runtime);
+        cloud
+    }
+
+    /// Starts a server that only accepts requests authenticated with `api_key`.
+    pub async fn start_with_api_key(api_key: impl Into<String>) -> Self {
+        let shared = Arc::new(Shared {
+            api_key: api_key.into(),
+            state: Mutex::new(CloudState::new()),
+            faults: Mutex::new(Faults::default()),
+        });
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("Failed to bind fake API server");
+        let addr = listener
+            .local_addr()
+            .expect("Failed to get fake API server address");
+
+        let router = routes::router(Arc::clone(&shared));
+        let server = tokio::spawn(async move {
+            axum::serve(listener, router)
+                .await
+                .expect("Fake API server failed");
+        });
+
+        Self {
+            addr,
+            shared,
+            server,
+            runtime: None,
+        }
+    }
+
+    /// The URL to point a client at, like `http://127.0.0.1:12345/`.
+    pub fn base_url(&self) -> String {
+        format!("http://{}/", self.addr)
+    }
+
+    pub fn api_key(&self) -> &str {
+        &self.shared.api_key
+    }
+
+    /// A client config pointed at this server with short retry backoffs to keep tests fast.
+    pub fn client_config(&self) -> ClientConfig {
+        Client::builder()
+            .base_url(self.base_url())
+            .retry_policy(RetryPolicy {
+                initial_backoff: Duration::from_millis(10),
+                max_backoff: Duration::from_millis(50),
+                ..Default::default()
+            })
+    }
+
+    /// A client authenticated with the API key of this server.
+    pub fn client(&self) -> Client {
+        self.client_config()
+            .build(self.shared.api_key.clone())
+            .expect("Failed to build client")
+    }
+
+    /// A [`blocking::Client`](oxyde_cloud_client::blocking::Client) authenticated with the API
+    /// key of this server. Use it with a server from [`FakeCloud::start_blocking`].
+    pub fn blocking_client(&self) -> oxyde_cloud_client::blocking::Client {
+        self.client_config()
+            .build_blocking(self.shared.api_key.clone())
+            .expect("Failed to build blocking client")
+    }
+
+    /// The stored data. Don't hold the guard across an `.await`, as requests block on it.
+    pub fn state(&self) -> MutexGuard<'_, CloudState> {
+        self.shared.state()
+    }
+
+    /// The faults injected into upcoming requests.
+    pub fn faults(&self) -> MutexGuard<'_, Faults> {
+        self.shared.faults()
+    }
+}
+
+impl Drop for FakeCloud {
+    fn drop(&mut self) {
+        self.server.abort();
+    }
+}
+
+pub(crate) fn sha256(contents: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(contents))
+}
@@ -0,0 +1,1083 @@
+use std::convert::Infallible;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::body::{Body, Bytes};
+use axum::extract::{DefaultBodyLimit, Multipart, Path, Request, State};
+use axum::http::{HeaderMap, StatusCode, header::AUTHORIZATION};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use chrono::{DateTime, Utc};
+use headers_core::Header;
+use oxyde_cloud_common::config::{CloudConfig, DomainsConfig};
+use oxyde_cloud_common::net::{
+    ApiToken, App, AppMeta, CheckAvailabilityResponse, CheckSecretsRequest, CheckSecretsResponse,
+    DeleteAppRequest, DeleteSecretRequest, DeployManifest, Deployment, DeploymentStatus, Domain,
+    DomainRequest, ErrorResponse, InviteMemberRequest, LogEntry, LogFilter, LogLevel, LogRequest,
+    LogStreamRequest, LoginResponse, ManifestResponse, NewAppRequest, NewBlobRequest,
+    NewTeamRequest, NewTokenRequest, NewTokenResponse, NewUploadSessionRequest,
+    RemoveMemberRequest, RevokeTokenRequest, RollbackRequest, RollbackResponse, Secret,
+    SecretScope, SecretsRequest, SetMemberRoleRequest, SetSecretRequest, SetTeamNameRequest,
+    SuccessResponse, Team, TeamInvitation, TeamMember, TeamRole, TransferAppRequest,
+    UploadDoneResponse, UploadSession, UploadSessionRequest,
+};
+use oxyde_cloud_common::secrets::is_valid_secret_name;
+use regex::Regex;
+
+use crate::Shared;
+use crate::state::{CloudState, SessionTarget};
+
+type Result<T> = std::result::Result<Json<T>, ApiError>;
+
+pub(crate) fn router(shared: Arc<Shared>) -> Router {
+    Router::new()
+        .route("/teams", get(teams))
+        .route("/teams/new", post(new_team))
+        .route("/teams/name", post(set_team_name))
+        .route("/teams/:team_slug/members", get(team_members))
+        .route("/teams/:team_slug/invitations", get(team_invitations))
+        .route("/teams/invite", post(invite_member))
+        .route("/teams/remove-member", post(remove_member))
+        .route("/teams/role", post(set_member_role))
+        .route("/apps", get(apps))
+        .route("/apps/:app_slug", get(app))
+        .route("/apps/new", post(new_app))
+        .route("/apps/delete", post(delete_app))
+        .route("/apps/transfer", post(transfer_app))
+        .route("/apps/manifest", post(manifest))
+        .route("/apps/upload-sessions/new", post(new_upload_session))
+        .route("/apps/upload-sessions/status", post(upload_session))
+        .route("/apps/blobs/new", post(new_blob_session))
+        .route("/apps/upload-file", post(upload_file))
+        .route("/apps/upload-done", post(upload_done))
+        .route("/apps/:app_slug/deployments", get(deployments))
+        .route("/apps/:app_slug/domains", get(domains))
+        .route("/apps/:app_slug/domains/:domain", get(domain))
+        .route("/apps/domains/new", post(add_domain))
+        .route("/apps/domains/verify", post(verify_domain))
+        .route("/apps/domains/remove", post(remove_domain))
+        .route("/apps/rollback", post(rollback))
+        .route("/deployments/:id", get(deployment))
+        .route("/secrets/set", post(set_secret))
+        .route("/secrets/list", post(list_secrets))
+        .route("/secrets/delete", post(delete_secret))
+        .route("/secrets/check", post(check_secrets))
+        .route("/tokens", get(tokens))
+        .route("/tokens/new", post(new_token))
+        .route("/tokens/revoke", post(revoke_token))
+        .route("/log", post(log))
+        .route("/log/stream", post(log_stream))
+        .route("/login", post(login))
+        // Chunks are as large as the client wants them to be.
+        .layer(DefaultBodyLimit::disable())
+        .layer(middleware::from_fn_with_state(shared.clone(), faults))
+        .with_state(shared)
+}
+
+/// Records the request, applies the configured [`Faults`](crate::Faults) and checks the API key.
+async fn faults(State(shared): State<Arc<Shared>>, request: Request, next: Next) -> Response {
+    let route = request.uri().path().trim_start_matches('/').to_string();
+    shared.state().requests.push(route.clone());
+
+    let (latency, failure) = {
+        let mut faults = shared.faults();
+        (faults.latency, faults.take_failure(&route))
+    };
+
+    if let Some(latency) = latency {
+        tokio::time::sleep(latency).await;
+    }
+
+    if let Some(status) = failure {
+        return error(status, "Injected failure", Some("injected")).into_response();
+    }
+
+    let key = request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+    let authorized = match key {
+        Some(key) => key == shared.api_key || shared.state().use_token(key),
+        None => false,
+    };
+    if !authorized {
+        return error(StatusCode::UNAUTHORIZED, "Invalid API key", None).into_response();
+    }
+
+    next.run(request).await
+}
+
+async fn teams(State(shared): State<Arc<Shared>>) -> Json<Vec<Team>> {
+    let teams = shared
+        .state()
+        .teams
+        .iter()
+        .map(|(slug, name)| Team {
+            slug: slug.clone(),
+            name: name.clone(),
+        })
+        .collect();
+
+    Json(teams)
+}
+
+async fn new_team(
+    State(shared): State<Arc<Shared>>,
+    Json(request): Json<NewTeamRequest>,
+) -> Json<CheckAvailabilityResponse> {
+    let mut state = shared.state();
+
+    let available = !state.teams.contains_key(&request.team_slug);
+    if available {
+        let team_slug = request.team_slug;
+        state.add_team(&team_slug, &team_slug);
+    }
+
+    Json(CheckAvailabilityResponse { available })
+}
+
+async fn set_team_name(
+    State(shared): State<Arc<Shared>>,
+    Json(request): Json<SetTeamNameRequest>,
+) -> Result<SuccessResponse> {
+    let mut state = shared.state();
+
+    let Some(name) = state.teams.get_mut(&request.team_slug) else {
+        return Err(not_found("Team"));
+    };
+    *name = request.team_name;
+
+    Ok(Json(SuccessResponse::default()))
+}
+
+async fn team_members(
+    State(shared): State<Arc<Shared>>,
+    Path(team_slug): Path<String>,
+) -> Result<Vec<TeamMember>> {
+    let state = shared.state();
+
+    if !state.teams.contains_key(&team_slug) {
+        return Err(not_found("Team"));
+    }
+
+    let members = state
+        .members
+        .get(&team_slug)
+        .into_iter()
+        .flat_map(|members| members.values().cloned())
+        .collect();
+
+    Ok(Json(members))
+}
+
+async fn team_invitations(
+    State(shared): State<Arc<Shared>>,
+    Path(team_slug): Path<String>,
+) -> Result<Vec<TeamInvitation>> {
+    let state = shared.state();
+
+    if !state.teams.contains_key(&team_slug) {
+        return Err(not_found("Team"));
+    }
+
+    Ok(Json(
+        state
+            .invitations
+            .get(&team_slug)
+            .cloned()
+            .unwrap_or_default(),
+    ))
+}
+
+async fn invite_member(
+    State(shared): State<Arc<Shared>>,
+    Json(request): Json<InviteMemberRequest>,
+) -> Result<TeamInvitation> {
+    let mut state = shared.state();
+
+    if !state.teams.contains_key(&request.team_slug) {
+        return Err(not_found("Team"));
+    }
+
+    let already_invited = state
+        .invitations
+        .get(&request.team_slug)
+        .is_some_and(|invitations| invitations.iter().any(|i| i.email == request.email));
+    if already_invited {
+        return Err(error(
+            StatusCode::CONFLICT,
+            "This email has already been invited",
+            Some("already_invited"),
+        ));
+    }
+
+    Ok(Json(state.invite(
+        &request.team_slug,
+        &request.email,
+        request.role,
+    )))
+}
+
+/// Fails unless `username` is a member of the team and the team keeps an owner if their role
+/// changes to `new_role`, or they're removed if it's `None`.
+fn check_member_change(
+    state: &CloudState,
+    team_slug: &str,
+    username: &str,
+    new_role: Option<TeamRole>,
+) -> std::result::Result<(), ApiError> {
+    let Some(members) = state.members.get(team_slug) else {
+        return Err(not_found("Team"));
+    };
+    let Some(member) = members.get(username) else {
+        return Err(not_found("Member"));
+    };
+
+    let owners = members
+        .values()
+        .filter(|m| m.role == TeamRole::Owner)
+        .count();
+    if member.role == TeamRole::Owner && new_role != Some(TeamRole::Owner) && owners == 1 {
+        return Err(error(
+            StatusCode::CONFLICT,
+            "A team needs at least one owner",
+            Some("last_owner"),
+        ));
+    }
+
+    Ok(())
+}
+
+async fn remove_member(
+    State(shared): State<Arc<Shared>>,
+    Json(request): Json<RemoveMemberRequest>,
+) -> Result<SuccessResponse> {
+    let mut state = shared.state();
+    check_member_change(&state, &request.team_slug, &request.username, None)?;
+
+    if let Some(members) = state.members.get_mut(&request.team_slug) {
+        members.remove(&request.username);
+    }
+
+    Ok(Json(SuccessResponse::default()))
+}
+
+async fn set_member_role(
+    State(shared): State<Arc<Shared>>,
+    Json(request): Json<SetMemberRoleRequest>,
+) -> Result<SuccessResponse> {
+    let mut state = shared.state();
+    check_member_change(
+        &state,
+        &request.team_slug,
+        &request.username,
+        Some(request.role),
+    )?;
+
+    if let Some(member) = state
+        .members
+        .get_mut(&request.team_slug)
+        .and_then(|members| members.get_mut(&request.username))
+    {
+        member.role = request.role;
+    }
+
+    Ok(Json(SuccessResponse::default()))
+}
+
+async fn new_app(
+    State(shared): State<Arc<Shared>>,
+    Json(request): Json<NewAppRequest>,
+) -> Result<CheckAvailabilityResponse> {
+    let mut state = shared.state();
+
+    if !state.teams.contains_key(&request.team_slug) {
+        return Err(not_found("Team"));
+    }
+
+    let available = !state.apps.contains_key(&request.app_slug);
+    if available {
+        state
+            .app_names
+            .insert(request.app_slug.clone(), request.name);
+        state.apps.insert(request.app_slug, request.team_slug);
+    }
+
+    Ok(Json(CheckAvailabilityResponse { available }))
+}
+
+fn app_info(state: &CloudState, slug: &str) -> Option<App> {
+    let team_slug = state.apps.get(slug)?;
+
+    Some(App {
+        slug: slug.to_string(),
+        name: state
+            .app_names
+            .get(slug)
+            .cloned()
+            .unwrap_or_else(|| slug.to_string()),
+        team_slug: team_slug.clone(),
+    })
+}
+
+async fn apps(State(shared): State<Arc<Shared>>) -> Json<Vec<App>> {
+    let state = shared.state();
+
+    let apps = state
+        .apps
+        .keys()
+        .filter_map(|slug| app_info(&state, slug))
+        .collect();
+
+    Json(apps)
+}
+
+async fn app(State(shared): State<Arc<Shared>>, Path(app_slug): Path<String>) -> Result<App> {
+    app_info(&shared.state(), &app_slug)
+        .map(Json)
+        .ok_or_else(|| not_found("App"))
+}
+
+async fn delete_app(
+    State(shared): State<Arc<Shared>>,
+    Json(request): Json<DeleteAppRequest>,
+) -> Result<SuccessResponse> {
+    if !shared.state().remove_app(&request.app_slug) {
+        return Err(not_found("App"));
+    }
+
+    Ok(Json(SuccessResponse::default()))
+}
+
+async fn transfer_app(
+    State(shared): State<Arc<Shared>>,
+    Json(request): Json<TransferAppRequest>,
+) -> Result<SuccessResponse> {
+    let mut state = shared.state();
+
+    if !state.teams.contains_key(&request.team_slug) {
+        return Err(not_found("Team"));
+    }
+
+    let Some(team_slug) = state.apps.get_mut(&request.app_slug) else {
+        return Err(not_found("App"));
+    };
+    *team_slug = request.team_slug;
+
+    Ok(Json(SuccessResponse::default()))
+}
+
+async fn manifest(
+    State(shared): State<Arc<Shared>>,
+    Json(manifest): Json<DeployManifest>,
+) -> Result<ManifestResponse> {
+    let mut state = shared.state();
+
+    if !state.apps.contains_key(&manifest.app_slug) {
+        return Err(not_found("App"));
+    }
+
+    let mut missing_blobs = Vec::<String>::new();
+    for entry in &manifest.files {
+        if !state.blobs.contains_key(&entry.sha256) && !missing_blobs.contains(&entry.sha256) {
+            missing_blobs.push(entry.sha256.clone());
+        }
+    }
+
+    state.manifests.insert(manifest.app_slug.clone(), manifest);
+
+    Ok(Json(ManifestResponse { missing_blobs }))
+}
+
+async fn new_upload_session(
+    State(shared): State<Arc<Shared>>,
+    Json(request): Json<NewUploadSessionRequest>,
+) -> Result<UploadSession> {
+    let target = SessionTarget::File {
+        file_name: request.file_name,
+        sha256: request.sha256,
+    };
+
+    open_session(
+        &shared,
+        request.app_slug,
+        target,
+        request.size,
+        request.chunk_size,
+    )
+}
+
+async fn new_blob_session(
+    State(shared): State<Arc<Shared>>,
+    Json(request): Json<NewBlobRequest>,
+) -> Result<UploadSession> {
+    let target = SessionTarget::Blob {
+        sha256: request.sha256,
+    };
+
+    open_session(
+        &shared,
+        request.app_slug,
+        target,
+        request.size,
+        request.chunk_size,
+    )
+}
+
+fn open_session(
+    shared: &Shared,
+    app_slug: String,
+    target: SessionTarget,
+    size: u64,
+    chunk_size: u64,
+) -> Result<UploadSession> {
+    let mut state = shared.state();
+
+    if !state.apps.contains_key(&app_slug) {
+        return Err(not_found("App"));
+    }
+
+    let session_id = state.open_session(app_slug, target, size, chunk_size);
+    let chunk_size = state.chunk_size.unwrap_or(chunk_size);
+
+    Ok(Json(UploadSession {
+        session_id,
+        chunk_size,
+        received_chunks: Vec::new(),
+    }))
+}
+
+async fn upload_session(
+    State(shared): State<Arc<Shared>>,
+    Json(request): Json<UploadSessionRequest>,
+) -> Result<UploadSession> {
+    let state = shared.state();
+
+    let Some(session) = state.sessions.get(&request.session_id) else {
+        return Err(not_found("Upload session"));
+    };
+
+    Ok(Json(UploadSession {
+        session_id: request.session_id,
+        chunk_size: session.chunk_size,
+        received_chunks: session.chunks.keys().copied().collect(),
+    }))
+}
+
+async fn upload_file(
+    State(shared): State<Arc<Shared>>,
+    headers: HeaderMap,
+    mut multipart: Multipart,
+) -> Result<SuccessResponse> {
+    let mut contents = None;
+    let mut session_id = None;
+    let mut chunk_number = None;
+
+    while let Some(field) = multipart.next_field().await.map_err(bad_request)? {
+        match field.name() {
+            Some("file") => contents = Some(field.bytes().await.map_err(bad_request)?),
+            Some("session_id") => session_id = Some(field.text().await.map_err(bad_request)?),
+            Some("chunk_number") => {
+                let text = field.text().await.map_err(bad_request)?;
+                chunk_number = Some(text.parse::<u64>().map_err(bad_request)?);
+            }
+            _ => {}
+        }
+    }
+
+    let (Some(contents), Some(session_id), Some(chunk_number)) =
+        (contents, session_id, chunk_number)
+    else {
+        return Err(bad_request("Missing file, session_id or chunk_number"));
+    };
+
+    let app_slug = headers
+        .get(AppMeta::name())
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+
+    if shared.faults().take_dropped_chunk() {
+        return Ok(Json(SuccessResponse::default()));
+    }
+
+    let mut state = shared.state();
+
+    let Some(session) = state.sessions.get_mut(&session_id) else {
+        return Err(not_found("Upload session"));
+    };
+
+    if app_slug.as_ref() != Some(&session.app_slug) {
+        return Err(bad_request(
+            "App meta header doesn't match the upload session",
+        ));
+    }
+    if chunk_number >= session.chunk_count() {
+        return Err(bad_request("Chunk number out of range"));
+    }
+    if contents.len() as u64 != session.chunk_len(chunk_number) {
+        return Err(bad_request("Unexpected chunk length"));
+    }
+
+    session.chunks.insert(chunk_number, contents.to_vec());
+
+    if session.is_complete() {
+        let session = state.sessions.remove(&session_id).expect("session exists");
+
+        if let Err(message) = state.store(session) {
+            return Err(error(
+                StatusCode::UNPROCESSABLE_ENTITY,
+                &message,
+                Some("checksum_mismatch"),
+            ));
+        }
+    }
+
+    Ok(Json(SuccessResponse::default()))
+}
+
+async fn upload_done(
+    State(shared): State<Arc<Shared>>,
+    Json(config): Json<CloudConfig>,
+) -> Result<UploadDoneResponse> {
+    let mut state = shared.state();
+
+    if !state.apps.contains_key(&config.app.slug) {
+        return Err(not_found("App"));
+    }
+
+    if let Some(runtime) = &config.runtime
+        && let Err(err) = runtime.validate()
+    {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            &err.to_string(),
+            Some("invalid_runtime"),
+        ));
+    }
+
+    if let Some(health) = &config.health
+        && let Err(err) = health.validate()
+    {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            &err.to_string(),
+            Some("invalid_health"),
+        ));
+    }
+
+    if let Some(manifest) = state.manifests.get(&config.app.slug) {
+        let missing = manifest
+            .files
+            .iter()
+            .filter(|entry| !state.blobs.contains_key(&entry.sha256))
+            .count();
+
+        if missing > 0 {
+            return Err(error(
+                StatusCode::CONFLICT,
+                &format!("{missing} files of the manifest haven't been uploaded"),
+                Some("missing_blobs"),
+            ));
+        }
+    }
+
+    let size = match state.manifests.get(&config.app.slug) {
+        Some(manifest) => manifest.files.iter().map(|entry| entry.size).sum(),
+        None => state
+            .files
+            .get(&config.app.slug)
+            .map(|files| files.values().map(|file| file.len() as u64).sum())
+            .unwrap_or_default(),
+    };
+    let deployment_id = state.start_deployment(&config.app.slug, size);
+
+    match config.runtime {
+        Some(runtime) => state.runtimes.insert(config.app.slug, runtime),
+        None => state.runtimes.remove(&config.app.slug),
+    };
+
+    Ok(Json(UploadDoneResponse { deployment_id }))
+}
+
+async fn deployments(
+    State(shared): State<Arc<Shared>>,
+    Path(app_slug): Path<String>,
+) -> Result<Vec<Deployment>> {
+    let state = shared.state();
+
+    if !state.apps.contains_key(&app_slug) {
+        return Err(not_found("App"));
+    }
+
+    let deployments = state
+        .deployments
+        .iter()
+        .rev()
+        .filter(|deployment| deployment.app_slug == app_slug)
+        .cloned()
+        .collect();
+
+    Ok(Json(deployments))
+}
+
+async fn deployment(
+    State(shared): State<Arc<Shared>>,
+    Path(id): Path<String>,
+) -> Result<Deployment> {
+    shared
+        .state()
+        .poll_deployment(&id)
+        .map(Json)
+        .ok_or_else(|| not_found("Deployment"))
+}
+
+async fn domains(
+    State(shared): State<Arc<Shared>>,
+    Path(app_slug): Path<String>,
+) -> Result<Vec<Domain>> {
+    let state = shared.state();
+
+    if !state.apps.contains_key(&app_slug) {
+        return Err(not_found("App"));
+    }
+
+    let domains = state
+        .domains
+        .values()
+        .filter(|domain| domain.app_slug == app_slug)
+        .cloned()
+        .collect();
+
+    Ok(Json(domains))
+}
+
+async fn domain(
+    State(shared): State<Arc<Shared>>,
+    Path((app_slug, domain)): Path<(String, String)>,
+) -> Result<Domain> {
+    shared
+        .state()
+        .domains
+        .get(&domain)
+        .filter(|domain| domain.app_slug == app_slug)
+        .cloned()
+        .map(Json)
+        .ok_or_else(|| not_found("Domain"))
+}
+
+async fn add_domain(
+    State(shared): State<Arc<Shared>>,
+    Json(request): Json<DomainRequest>,
+) -> Result<Domain> {
+    let mut state = shared.state();
+
+    if !state.apps.contains_key(&request.app_slug) {
+        return Err(not_found("App"));
+    }
+
+    if !DomainsConfig::is_valid_domain(&request.domain) {
+        return Err(bad_request(format!("Invalid domain '{}'", request.domain)));
+    }
+
+    if state.domains.contains_key(&request.domain) {
+        return Err(error(
+            StatusCode::CONFLICT,
+            "This domain is already in use",
+            Some("domain_taken"),
+        ));
+    }
+
+    Ok(Json(state.add_domain(&request.app_slug, &request.domain)))
+}
+
+/// Looks up a domain of an app and fails if it belongs to another app.
+fn check_domain(state: &CloudState, request: &DomainRequest) -> std::result::Result<(), ApiError> {
+    match state.domains.get(&request.domain) {
+        Some(domain) if domain.app_slug == request.app_slug => Ok(()),
+        _ => Err(not_found("Domain")),
+    }
+}
+
+async fn verify_domain(
+    State(shared): State<Arc<Shared>>,
+    Json(request): Json<DomainRequest>,
+) -> Result<Domain> {
+    let mut state = shared.state();
+    check_domain(&state, &request)?;
+
+    state
+        .verify_domain(&request.domain)
+        .map(Json)
+        .ok_or_else(|| not_found("Domain"))
+}
+
+async fn remove_domain(
+    State(shared): State<Arc<Shared>>,
+    Json(request): Json<DomainRequest>,
+) -> Result<SuccessResponse> {
+    let mut state = shared.state();
+    check_domain(&state, &request)?;
+
+    state.domains.remove(&request.domain);
+
+    Ok(Json(SuccessResponse::default()))
+}
+
+async fn rollback(
+    State(shared): State<Arc<Shared>>,
+    Json(request): Json<RollbackRequest>,
+) -> Result<RollbackResponse> {
+    let mut state = shared.state();
+
+    let Some(target) = state
+        .deployments
+        .iter()
+        .find(|d| d.id == request.deployment_id && d.app_slug == request.app_slug)
+        .cloned()
+    else {
+        return Err(not_found("Deployment"));
+    };
+
+    if target.status != DeploymentStatus::Success {
+        return Err(error(
+            StatusCode::CONFLICT,
+            "Only successful deployments can be rolled back to",
+            Some("deployment_not_successful"),
+        ));
+    }
+
+    let deployment_id = state.start_deployment(&target.app_slug, target.size);
+    if let Some(deployment) = state.deployments.last_mut() {
+        deployment.rolled_back_from = Some(target.release_id().to_string());
+        deployment.commit_sha = target.commit_sha;
+    }
+
+    Ok(Json(RollbackResponse { deployment_id }))
+}
+
+/// Fails if the app or team a secret belongs to doesn't exist.
+fn check_scope(state: &CloudState, scope: &SecretScope) -> std::result::Result<(), ApiError> {
+    match scope {
+        SecretScope::App(slug) if !state.apps.contains_key(slug) => Err(not_found("App")),
+        SecretScope::Team(slug) if !state.teams.contains_key(slug) => Err(not_found("Team")),
+        _ => Ok(()),
+    }
+}
+
+async fn set_secret(
+    State(shared): State<Arc<Shared>>,
+    Json(request): Json<SetSecretRequest>,
+) -> Result<SuccessResponse> {
+    let mut state = shared.state();
+    check_scope(&state, &request.scope)?;
+
+    if !is_valid_secret_name(&request.name) {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "Secret names may only contain letters, digits and underscores",
+            Some("invalid_secret_name"),
+        ));
+    }
+
+    state.set_secret(request.scope, &request.name, &request.value);
+
+    Ok(Json(SuccessResponse::default()))
+}
+
+async fn list_secrets(
+    State(shared): State<Arc<Shared>>,
+    Json(request): Json<SecretsRequest>,
+) -> Result<Vec<Secret>> {
+    let state = shared.state();
+    check_scope(&state, &request.scope)?;
+
+    let secrets = state
+        .secrets
+        .get(&request.scope)
+        .into_iter()
+        .flatten()
+        .map(|(name, secret)| Secret {
+            name: name.clone(),
+            updated_at: secret.updated_at,
+        })
+        .collect();
+
+    Ok(Json(secrets))
+}
+
+async fn delete_secret(
+    State(shared): State<Arc<Shared>>,
+    Json(request): Json<DeleteSecretRequest>,
+) -> Result<SuccessResponse> {
+    let mut state = shared.state();
+    check_scope(&state, &request.scope)?;
+
+    state
+        .secrets
+        .get_mut(&request.scope)
+        .and_then(|secrets| secrets.remove(&request.name))
+        .ok_or_else(|| not_found("Secret"))?;
+
+    Ok(Json(SuccessResponse::default()))
+}
+
+async fn check_secrets(
+    State(shared): State<Arc<Shared>>,
+    Json(request): Json<CheckSecretsRequest>,
+) -> Result<CheckSecretsResponse> {
+    let state = shared.state();
+
+    let Some(team_slug) = state.apps.get(&request.app_slug) else {
+        return Err(not_found("App"));
+    };
+    let app = SecretScope::App(request.app_slug.clone());
+    let team = SecretScope::Team(team_slug.clone());
+
+    let missing = request
+        .names
+        .into_iter()
+        .filter(|name| state.secret(&app, name).is_none() && state.secret(&team, name).is_none())
+        .collect();
+
+    Ok(Json(CheckSecretsResponse { missing }))
+}
+
+async fn tokens(State(shared): State<Arc<Shared>>) -> Json<Vec<ApiToken>> {
+    let tokens = shared
+        .state()
+        .tokens
+        .values()
+        .map(|(token, _)| token.clone())
+        .collect();
+
+    Json(tokens)
+}
+
+async fn new_token(
+    State(shared): State<Arc<Shared>>,
+    Json(request): Json<NewTokenRequest>,
+) -> Result<NewTokenResponse> {
+    if request.scopes.is_empty() {
+        return Err(bad_request("A token needs at least one scope"));
+    }
+
+    Ok(Json(shared.state().create_token(request)))
+}
+
+async fn revoke_token(
+    State(shared): State<Arc<Shared>>,
+    Json(request): Json<RevokeTokenRequest>,
+) -> Result<SuccessResponse> {
+    shared
+        .state()
+        .tokens
+        .remove(&request.id)
+        .ok_or_else(|| not_found("Token"))?;
+
+    Ok(Json(SuccessResponse::default()))
+}
+
+/// The filters `log` and `log/stream` have in common.
+struct LogSelection {
+    since: Option<DateTime<Utc>>,
+    until: Option<DateTime<Utc>>,
+    level: Option<LogLevel>,
+    instance: Option<String>,
+    filter: Option<LogFilter>,
+    regex: Option<Regex>,
+}
+
+impl LogSelection {
+    fn new(
+        since: Option<DateTime<Utc>>,
+        until: Option<DateTime<Utc>>,
+        level: Option<LogLevel>,
+        instance: Option<String>,
+        filter: Option<LogFilter>,
+    ) -> std::result::Result<Self, ApiError> {
+        let regex = match &filter {
+            Some(LogFilter::Regex(pattern)) => Some(Regex::new(pattern).map_err(bad_request)?),
+            _ => None,
+        };
+
+        Ok(Self {
+            since,
+            until,
+            level,
+            instance,
+            filter,
+            regex,
+        })
+    }
+
+    fn matches(&self, entry: &LogEntry) -> bool {
+        self.since.is_none_or(|since| entry.timestamp >= since)
+            && self.until.is_none_or(|until| entry.timestamp < until)
+            && self.level.is_none_or(|level| entry.level >= level)
+            && self
+                .instance
+                .as_ref()
+                .is_none_or(|instance| &entry.instance == instance)
+            && match &self.filter {
+                Some(LogFilter::Text(text)) => entry.message.contains(text.as_str()),
+                Some(LogFilter::Regex(_)) => self
+                    .regex
+                    .as_ref()
+                    .is_some_and(|regex| regex.is_match(&entry.message)),
+                None => true,
+            }
+    }
+}
+
+async fn log(
+    State(shared): State<Arc<Shared>>,
+    Json(request): Json<LogRequest>,
+) -> Result<Vec<LogEntry>> {
+    let selection = LogSelection::new(
+        request.since,
+        request.until,
+        request.level,
+        request.instance,
+        request.filter,
+    )?;
+
+    let state = shared.state();
+    let Some(log) = state.logs.get(&request.name) else {
+        return Err(not_found("Log"));
+    };
+
+    let mut entries = log
+        .iter()
+        .filter(|entry| selection.matches(entry))
+        .cloned()
+        .collect::<Vec<_>>();
+
+    if let Some(tail) = request.tail {
+        entries.drain(..entries.len().saturating_sub(tail));
+    }
+
+    Ok(Json(entries))
+}
+
+/// Streams the matching entries of the log as newline delimited JSON with the entry's index as
+/// cursor. New entries are picked up by polling the state until the client disconnects.
+async fn log_stream(
+    State(shared): State<Arc<Shared>>,
+    Json(request): Json<LogStreamRequest>,
+) -> std::result::Result<Response, ApiError> {
+    let selection = LogSelection::new(
+        request.since,
+        None,
+        request.level,
+        request.instance,
+        request.filter,
+    )?;
+
+    let start = {
+        let state = shared.state();
+        let Some(log) = state.logs.get(&request.name) else {
+            return Err(not_found("Log"));
+        };
+
+        match (request.cursor, request.tail) {
+            (Some(cursor), _) => cursor.parse::<usize>().map_err(bad_request)? + 1,
+            (None, Some(tail)) => {
+                let matching = (0..log.len())
+                    .filter(|&index| selection.matches(&log[index]))
+                    .collect::<Vec<_>>();
+
+                matching
+                    .get(matching.len().saturating_sub(tail))
+                    .copied()
+                    .unwrap_or(log.len())
+            }
+            (None, None) => 0,
+        }
+    };
+    let limit = shared.faults().log_stream_limit();
+    let name = request.name;
+    let selection = Arc::new(selection);
+
+    let entries = futures_util::stream::unfold((start, 0), move |(mut index, sent)| {
+        let shared = Arc::clone(&shared);
+        let name = name.clone();
+        let selection = Arc::clone(&selection);
+
+        async move {
+            if limit.is_some_and(|limit| sent >= limit) {
+                return None;
+            }
+
+            loop {
+                let entry = shared
+                    .state()
+                    .logs
+                    .get(&name)
+                    .and_then(|log| log.get(index).cloned());
+
+                let Some(entry) = entry else {
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                    continue;
+                };
+
+                if !selection.matches(&entry) {
+                    index += 1;
+                    continue;
+                }
+
+                let entry = LogEntry {
+                    cursor: Some(index.to_string()),
+                    ..entry
+                };
+                let mut json = serde_json::to_vec(&entry).expect("log entry serializes");
+                json.push(b'\n');
+
+                return Some((
+                    Ok::<_, Infallible>(Bytes::from(json)),
+                    (index + 1, sent + 1),
+                ));
+            }
+        }
+    });
+
+    Ok(Body::from_stream(entries).into_response())
+}
+
+async fn login(State(shared): State<Arc<Shared>>) -> Json<LoginResponse> {
+    Json(LoginResponse {
+        username: shared.state().username.clone(),
+    })
+}
+
+/// An unsuccessful response with an [`ErrorResponse`] body.
+struct ApiError {
+    status: StatusCode,
+    body: ErrorResponse,
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.status, Json(self.body)).into_response()
+    }
+}
+
+fn error(status: StatusCode, message: &str, code: Option<&str>) -> ApiError {
+    ApiError {
+        status,
+        body: ErrorResponse {
+            error: message.to_string(),
+            code: code.map(str::to_string),
+        },
+    }
+}
+
+fn not_found(what: &str) -> ApiError {
+    error(StatusCode::NOT_FOUND, &format!("{what} not found"), None)
+}
+
+fn bad_request(err: impl ToString) -> ApiError {
+    error(StatusCode::BAD_REQUEST, &err.to_string(), None)
+}
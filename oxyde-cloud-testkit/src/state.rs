@@ -0,0 +1,494 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use chrono::{DateTime, Utc};
+use oxyde_cloud_common::net::{
+    ApiToken, CertificateStatus, DeployManifest, Deployment, DeploymentStatus, DnsRecord,
+    DnsRecordKind, Domain, DomainStatus, LogEntry, LogLevel, NewTokenRequest, NewTokenResponse,
+    OutputStream, SecretScope, TeamInvitation, TeamMember, TeamRole,
+};
+use oxyde_cloud_common::runtime::RuntimeConfig;
+
+/// Everything the fake API has stored. Seed it before and inspect it after exercising the
+/// client through [`FakeCloud::state`](crate::FakeCloud::state).
+#[derive(Debug)]
+pub struct CloudState {
+    /// Display names by team slug.
+    pub teams: BTreeMap<String, String>,
+
+    /// Members of each team by team slug and username.
+    pub members: BTreeMap<String, BTreeMap<String, TeamMember>>,
+
+    /// Pending invitations of each team by team slug.
+    pub invitations: BTreeMap<String, Vec<TeamInvitation>>,
+
+    /// Slug of the owning team by app slug.
+    pub apps: BTreeMap<String, String>,
+
+    /// Display names of apps created through `apps/new` by app slug. Other apps are named
+    /// after their slug.
+    pub app_names: BTreeMap<String, String>,
+
+    /// Custom domains of all apps by domain name.
+    pub domains: BTreeMap<String, Domain>,
+
+    /// Domains whose DNS records have been set up, so verifying them succeeds. Verification of
+    /// all other domains stays pending.
+    pub dns: BTreeSet<String>,
+
+    /// Log entries by log name in the order they were written.
+    pub logs: HashMap<String, Vec<LogEntry>>,
+
+    /// Contents of completely uploaded files by app slug and file name.
+    pub files: BTreeMap<String, BTreeMap<String, Vec<u8>>>,
+
+    /// Contents of completely uploaded blobs by their hex encoded SHA-256 hash.
+    pub blobs: HashMap<String, Vec<u8>>,
+
+    /// The last manifest submitted for each app.
+    pub manifests: HashMap<String, DeployManifest>,
+
+    /// The `[runtime]` section of the last accepted `apps/upload-done` by app slug.
+    pub runtimes: HashMap<String, RuntimeConfig>,
+
+    /// Deployments of all apps, oldest first. Every accepted `apps/upload-done` adds one.
+    pub deployments: Vec<Deployment>,
+
+    /// Secrets of the secret store by scope and name.
+    pub secrets: BTreeMap<SecretScope, BTreeMap<String, StoredSecret>>,
+
+    /// API tokens that haven't been revoked together with their secrets, by id.
+    pub tokens: BTreeMap<String, (ApiToken, String)>,
+
+    /// Upload sessions that haven't received all of their chunks yet.
+    pub sessions: HashMap<String, Session>,
+
+    /// The route of every request in order of arrival, including rejected ones.
+    pub requests: Vec<String>,
+
+    /// Returned by the `login` route.
+    pub username: String,
+
+    /// Chunk size handed out for new upload sessions instead of the one the client asked for.
+    /// Small values make uploads with many chunks cheap to test.
+    pub chunk_size: Option<u64>,
+
+    /// The status deployments started by `apps/upload-done` end up in. Defaults to success.
+    pub deployment_outcome: DeploymentStatus,
+
+    /// How many times a deployment started by `apps/upload-done` is reported as pending before
+    /// it reaches [`CloudState::deployment_outcome`].
+    pub deployment_pending_polls: usize,
+
+    next_session_id: u64,
+    next_deployment_id: u64,
+    next_invitation_id: u64,
+    next_token_id: u64,
+
+    /// Remaining pending polls and the outcome of deployments that haven't finished yet.
+    pending_deployments: HashMap<String, (usize, DeploymentStatus)>,
+}
+
+impl CloudState {
+    pub(crate) fn new() -> Self {
+        Self {
+            teams: BTreeMap::new(),
+            members: BTreeMap::new(),
+            invitations: BTreeMap::new(),
+            apps: BTreeMap::new(),
+            app_names: BTreeMap::new(),
+            domains: BTreeMap::new(),
+            dns: BTreeSet::new(),
+            logs: HashMap::new(),
+            files: BTreeMap::new(),
+            blobs: HashMap::new(),
+            manifests: HashMap::new(),
+            runtimes: HashMap::new(),
+            deployments: Vec::new(),
+            secrets: BTreeMap::new(),
+            tokens: BTreeMap::new(),
+            sessions: HashMap::new(),
+            requests: Vec::new(),
+            username: "tester".to_string(),
+            chunk_size: None,
+            deployment_outcome: DeploymentStatus::Success,
+            deployment_pending_polls: 0,
+            next_session_id: 0,
+            next_deployment_id: 0,
+            next_invitation_id: 0,
+            next_token_id: 0,
+            pending_deployments: HashMap::new(),
+        }
+    }
+
+    /// Adds a team with the given slug and display name, owned by [`CloudState::username`].
+    pub fn add_team(&mut self, slug: &str, name: &str) -> &mut Self {
+        self.teams.insert(slug.to_string(), name.to_string());
+        let username = self.username.clone();
+        self.add_member(slug, &username, TeamRole::Owner)
+    }
+
+    /// Adds a member to a team or changes their role.
+    pub fn add_member(&mut self, team_slug: &str, username: &str, role: TeamRole) -> &mut Self {
+        self.members
+            .entry(team_slug.to_string())
+            .or_default()
+            .insert(
+                username.to_string(),
+                TeamMember {
+                    username: username.to_string(),
+                    role,
+                    joined_at: Utc::now(),
+                },
+            );
+        self
+    }
+
+    pub(crate) fn invite(
+        &mut self,
+        team_slug: &str,
+        email: &str,
+        role: TeamRole,
+    ) -> TeamInvitation {
+        self.next_invitation_id += 1;
+
+        let invitation = TeamInvitation {
+            id: format!("invitation-{}", self.next_invitation_id),
+            email: email.to_string(),
+            role,
+            invited_by: self.username.clone(),
+            created_at: Utc::now(),
+        };
+        self.invitations
+            .entry(team_slug.to_string())
+            .or_default()
+            .push(invitation.clone());
+
+        invitation
+    }
+
+    /// Adds an app to an existing or new team.
+    pub fn add_app(&mut self, slug: &str, team_slug: &str) -> &mut Self {
+        self.teams
+            .entry(team_slug.to_string())
+            .or_insert_with(|| team_slug.to_string());
+        self.apps.insert(slug.to_string(), team_slug.to_string());
+        self
+    }
+
+    /// Removes an app and everything stored for it.
+    pub(crate) fn remove_app(&mut self, slug: &str) -> bool {
+        if self.apps.remove(slug).is_none() {
+            return false;
+        }
+
+        self.app_names.remove(slug);
+        self.logs.remove(slug);
+        self.files.remove(slug);
+        self.manifests.remove(slug);
+        self.deployments
+            .retain(|deployment| deployment.app_slug != slug);
+        self.secrets.remove(&SecretScope::App(slug.to_string()));
+        self.domains.retain(|_, domain| domain.app_slug != slug);
+
+        true
+    }
+
+    /// Adds a log called `name` with one info entry on stdout of `instance-1` per line of `log`.
+    pub fn add_log(&mut self, name: &str, log: &str) -> &mut Self {
+        self.logs.insert(name.to_string(), Vec::new());
+        for line in log.lines() {
+            self.append_log(name, line);
+        }
+        self
+    }
+
+    /// Appends an info entry on stdout of `instance-1` to the log called `name`. Followed log
+    /// streams pick it up right away.
+    pub fn append_log(&mut self, name: &str, message: &str) -> &mut Self {
+        self.append_log_entry(
+            name,
+            LogEntry {
+                timestamp: Utc::now(),
+                level: LogLevel::Info,
+                stream: OutputStream::Stdout,
+                instance: "instance-1".to_string(),
+                message: message.to_string(),
+                cursor: None,
+            },
+        )
+    }
+
+    /// Appends an entry to the log called `name`.
+    pub fn append_log_entry(&mut self, name: &str, entry: LogEntry) -> &mut Self {
+        self.logs.entry(name.to_string()).or_default().push(entry);
+        self
+    }
+
+    /// Adds a deployment of `app_slug` with the given status created by
+    /// [`CloudState::username`] and returns its id.
+    pub fn add_deployment(
+        &mut self,
+        app_slug: &str,
+        size: u64,
+        status: DeploymentStatus,
+    ) -> String {
+        self.next_deployment_id += 1;
+        let id = format!("deployment-{}", self.next_deployment_id);
+
+        self.deployments.push(Deployment {
+            id: id.clone(),
+            app_slug: app_slug.to_string(),
+            status,
+            created_at: Utc::now(),
+            creator: self.username.clone(),
+            commit_sha: None,
+            size,
+            rolled_back_from: None,
+        });
+
+        id
+    }
+
+    /// Starts a deployment that reaches [`CloudState::deployment_outcome`] after
+    /// [`CloudState::deployment_pending_polls`] polls.
+    pub(crate) fn start_deployment(&mut self, app_slug: &str, size: u64) -> String {
+        if self.deployment_pending_polls == 0 {
+            return self.add_deployment(app_slug, size, self.deployment_outcome);
+        }
+
+        let id = self.add_deployment(app_slug, size, DeploymentStatus::Pending);
+        self.pending_deployments.insert(
+            id.clone(),
+            (self.deployment_pending_polls, self.deployment_outcome),
+        );
+
+        id
+    }
+
+    /// Looks up a deployment for a status poll, which moves pending deployments along.
+    pub(crate) fn poll_deployment(&mut self, id: &str) -> Option<Deployment> {
+        if let Some((polls, outcome)) = self.pending_deployments.get_mut(id) {
+            if *polls > 0 {
+                *polls -= 1;
+            } else {
+                let outcome = *outcome;
+                self.pending_deployments.remove(id);
+
+                if let Some(deployment) = self.deployments.iter_mut().find(|d| d.id == id) {
+                    deployment.status = outcome;
+                }
+            }
+        }
+
+        self.deployments.iter().find(|d| d.id == id).cloned()
+    }
+
+    /// Creates the secret `name` in `scope` or replaces its value.
+    pub fn set_secret(&mut self, scope: SecretScope, name: &str, value: &str) -> &mut Self {
+        self.secrets.entry(scope).or_default().insert(
+            name.to_string(),
+            StoredSecret {
+                value: value.to_string(),
+                updated_at: Utc::now(),
+            },
+        );
+        self
+    }
+
+    /// The value of a secret.
+    pub fn secret(&self, scope: &SecretScope, name: &str) -> Option<&str> {
+        self.secrets
+            .get(scope)?
+            .get(name)
+            .map(|secret| secret.value.as_str())
+    }
+
+    /// Creates an API token. Its secret is accepted as API key until it's revoked or expires.
+    pub fn create_token(&mut self, request: NewTokenRequest) -> NewTokenResponse {
+        self.next_token_id += 1;
+        let id = format!("token-{}", self.next_token_id);
+        let secret = format!("oxy_test_{}", self.next_token_id);
+
+        let token = ApiToken {
+            id: id.clone(),
+            name: request.name,
+            scopes: request.scopes,
+            restriction: request.restriction,
+            created_at: Utc::now(),
+            expires_at: request.expires_at,
+            last_used_at: None,
+        };
+        self.tokens.insert(id, (token.clone(), secret.clone()));
+
+        NewTokenResponse { token, secret }
+    }
+
+    /// Looks up a valid token by its secret and marks it as used.
+    pub(crate) fn use_token(&mut self, secret: &str) -> bool {
+        let now = Utc::now();
+
+        let Some((token, _)) = self
+            .tokens
+            .values_mut()
+            .find(|(token, s)| s == secret && token.expires_at.is_none_or(|at| at > now))
+        else {
+            return false;
+        };
+        token.last_used_at = Some(now);
+
+        true
+    }
+
+    /// Adds a pending custom domain to an app.
+    pub fn add_domain(&mut self, app_slug: &str, name: &str) -> Domain {
+        let domain = Domain {
+            name: name.to_string(),
+            app_slug: app_slug.to_string(),
+            status: DomainStatus::Pending,
+            certificate: CertificateStatus::None,
+            records: vec![
+                DnsRecord {
+                    kind: DnsRecordKind::Txt,
+                    name: format!("_oxyde-challenge.{name}"),
+                    value: format!("oxyde-verify={}", &crate::sha256(name.as_bytes())[..32]),
+                },
+                DnsRecord {
+                    kind: DnsRecordKind::Cname,
+                    name: name.to_string(),
+                    value: format!("{app_slug}.oxydecloud.com"),
+                },
+            ],
+            verified_at: None,
+        };
+        self.domains.insert(name.to_string(), domain.clone());
+
+        domain
+    }
+
+    /// Verifies a domain if its DNS records are in [`CloudState::dns`]. The certificate of a
+    /// verified domain is issued right away.
+    pub(crate) fn verify_domain(&mut self, name: &str) -> Option<Domain> {
+        let published = self.dns.contains(name);
+        let domain = self.domains.get_mut(name)?;
+
+        if published && domain.status == DomainStatus::Pending {
+            domain.status = DomainStatus::Verified;
+            domain.certificate = CertificateStatus::Issued;
+            domain.verified_at = Some(Utc::now());
+        }
+
+        Some(domain.clone())
+    }
+
+    /// The contents of a completely uploaded file.
+    pub fn file(&self, app_slug: &str, file_name: &str) -> Option<&[u8]> {
+        self.files.get(app_slug)?.get(file_name).map(Vec::as_slice)
+    }
+
+    /// How many requests have been sent to `route`, like `"apps/upload-file"`.
+    pub fn hits(&self, route: &str) -> usize {
+        self.requests.iter().filter(|r| *r == route).count()
+    }
+
+    pub(crate) fn open_session(
+        &mut self,
+        app_slug: String,
+        target: SessionTarget,
+        size: u64,
+        chunk_size: u64,
+    ) -> String {
+        self.next_session_id += 1;
+        let session_id = format!("session-{}", self.next_session_id);
+
+        let session = Session {
+            app_slug,
+            target,
+            size,
+            chunk_size: self.chunk_size.unwrap_or(chunk_size),
+            chunks: BTreeMap::new(),
+        };
+
+        self.sessions.insert(session_id.clone(), session);
+
+        session_id
+    }
+
+    /// Stores the contents of a complete session. Fails if they don't match the expected hash.
+    pub(crate) fn store(&mut self, session: Session) -> Result<(), String> {
+        let contents = session.chunks.into_values().flatten().collect::<Vec<_>>();
+        let sha256 = crate::sha256(&contents);
+
+        match session.target {
+            SessionTarget::File {
+                file_name,
+                sha256: expected,
+            } => {
+                if expected.is_some_and(|expected| expected != sha256) {
+                    return Err(format!("Checksum of {file_name} doesn't match"));
+                }
+
+                self.files
+                    .entry(session.app_slug)
+                    .or_default()
+                    .insert(file_name, contents);
+            }
+            SessionTarget::Blob { sha256: expected } => {
+                if expected != sha256 {
+                    return Err(format!("Checksum of blob {expected} doesn't match"));
+                }
+
+                self.blobs.insert(sha256, contents);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A secret together with its value, which the API never returns.
+#[derive(Debug, Clone)]
+pub struct StoredSecret {
+    pub value: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// An upload session that is still waiting for chunks.
+#[derive(Debug, Clone)]
+pub struct Session {
+    pub app_slug: String,
+    pub target: SessionTarget,
+    pub size: u64,
+    pub chunk_size: u64,
+
+    /// Contents of the received chunks by chunk number.
+    pub chunks: BTreeMap<u64, Vec<u8>>,
+}
+
+impl Session {
+    /// Empty files are uploaded as a single empty chunk.
+    pub fn chunk_count(&self) -> u64 {
+        self.size.div_ceil(self.chunk_size).max(1)
+    }
+
+    /// The expected length of chunk `number`. Only the last chunk can be shorter.
+    pub fn chunk_len(&self, number: u64) -> u64 {
+        self.chunk_size
+            .min(self.size.saturating_sub(number * self.chunk_size))
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.chunks.len() as u64 == self.chunk_count()
+    }
+}
+
+/// What an upload session uploads.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SessionTarget {
+    File {
+        file_name: String,
+        sha256: Option<String>,
+    },
+    Blob {
+        sha256: String,
+    },
+}
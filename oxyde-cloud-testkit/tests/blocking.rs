@@ -0,0 +1,166 @@
+//! The tests in `client.rs` run against the blocking client as well. These check what only the
+//! blocking client has: its runtime, reading from `std::io::Read`, iterating over followed logs
+//! and being called from the wrong context.
+
+use std::io::Read;
+use std::time::Duration;
+
+use oxyde_cloud_client::ClientError;
+use oxyde_cloud_common::net::{DeploymentStatus, LogStreamRequest, Team};
+use oxyde_cloud_testkit::fixtures::cloud_config;
+use oxyde_cloud_testkit::{FakeCloud, StatusCode};
+
+/// Hands out at most `step` bytes per read, so a chunk takes many reads.
+struct Trickle<'a> {
+    data: &'a [u8],
+    step: usize,
+}
+
+impl Read for Trickle<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.step.min(buf.len()).min(self.data.len());
+        buf[..n].copy_from_slice(&self.data[..n]);
+        self.data = &self.data[n..];
+        Ok(n)
+    }
+}
+
+#[test]
+fn shares_the_runtime_between_clones_and_threads() {
+    let cloud = FakeCloud::start_blocking();
+    cloud.state().add_team("my-team", "My Team");
+    let client = cloud.blocking_client();
+
+    let threads = (0..4)
+        .map(|_| {
+            let client = client.clone();
+            std::thread::spawn(move || client.teams().unwrap())
+        })
+        .collect::<Vec<_>>();
+
+    for thread in threads {
+        assert_eq!(thread.join().unwrap()[0].slug, "my-team");
+    }
+    assert_eq!(cloud.state().hits("teams"), 4);
+}
+
+#[test]
+fn drives_timers_on_its_runtime() {
+    let cloud = FakeCloud::start_blocking();
+    {
+        let mut state = cloud.state();
+        state.add_app("my-app", "my-team");
+        state.deployment_pending_polls = 2;
+    }
+    cloud
+        .faults()
+        .fail_next("teams", StatusCode::SERVICE_UNAVAILABLE);
+    let client = cloud.blocking_client();
+
+    // Both retry backoffs and deployment polling sleep on the internal runtime.
+    client.teams().unwrap();
+    assert_eq!(cloud.state().hits("teams"), 2);
+
+    let id = client.upload_done(&cloud_config("my-app")).unwrap();
+    let deployment = client
+        .wait_for_deployment(&id, Duration::from_secs(10))
+        .unwrap();
+    assert_eq!(deployment.status, DeploymentStatus::Success);
+}
+
+#[test]
+fn uploads_from_std_readers() {
+    let cloud = FakeCloud::start_blocking();
+    cloud.state().add_app("my-app", "my-team").chunk_size = Some(4);
+
+    let reader = Trickle {
+        data: b"0123456789",
+        step: 3,
+    };
+    cloud
+        .blocking_client()
+        .upload_reader("my-app", "generated.txt", reader, 10)
+        .unwrap();
+
+    let state = cloud.state();
+    assert_eq!(
+        state.file("my-app", "generated.txt"),
+        Some(&b"0123456789"[..])
+    );
+    assert_eq!(state.hits("apps/upload-file"), 3);
+}
+
+#[test]
+fn fails_on_readers_that_end_early() {
+    let cloud = FakeCloud::start_blocking();
+    cloud.state().add_app("my-app", "my-team");
+
+    let err = cloud
+        .blocking_client()
+        .upload_reader("my-app", "generated.txt", &b"short"[..], 10)
+        .unwrap_err();
+
+    let ClientError::Io { source, .. } = err else {
+        panic!("unexpected error: {err:?}");
+    };
+    assert_eq!(source.kind(), std::io::ErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn passes_errors_through() {
+    let cloud = FakeCloud::start_blocking();
+    let client = cloud.blocking_client();
+
+    let err = client.deployment("unknown").unwrap_err();
+    assert!(matches!(err, ClientError::NotFound { .. }), "{err:?}");
+
+    let err = cloud
+        .client_config()
+        .build_blocking("wrong".to_string())
+        .unwrap()
+        .teams()
+        .unwrap_err();
+    assert!(matches!(err, ClientError::Unauthorized { .. }), "{err:?}");
+
+    let mut entries = client.log_stream(&LogStreamRequest::new("unknown"));
+    let err = entries.next().unwrap().unwrap_err();
+    assert!(matches!(err, ClientError::NotFound { .. }), "{err:?}");
+    assert!(entries.next().is_none());
+}
+
+#[test]
+fn follows_logs_as_an_iterator() {
+    let cloud = FakeCloud::start_blocking();
+    cloud.state().add_log("my-app", "one\ntwo");
+
+    let mut entries = cloud
+        .blocking_client()
+        .log_stream(&LogStreamRequest::new("my-app"));
+    assert_eq!(entries.next().unwrap().unwrap().message, "one");
+    assert_eq!(entries.next().unwrap().unwrap().message, "two");
+
+    cloud.state().append_log("my-app", "three");
+    assert_eq!(entries.next().unwrap().unwrap().message, "three");
+}
+
+#[test]
+fn sends_requests_to_custom_routes() {
+    let cloud = FakeCloud::start_blocking();
+    cloud.state().add_team("my-team", "My Team");
+
+    let teams: Vec<Team> = cloud.blocking_client().get("teams").send().unwrap();
+    assert_eq!(teams[0].slug, "my-team");
+}
+
+#[test]
+#[should_panic(expected = "runtime")]
+fn panics_inside_an_async_runtime() {
+    let cloud = FakeCloud::start_blocking();
+    let client = cloud.blocking_client();
+
+    tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap()
+        .block_on(async { client.teams() })
+        .unwrap();
+}
@@ -0,0 +1,887 @@
+//! Every test runs twice, once against the async client and once against the blocking one.
+
+use std::io::Read;
+use std::path::Path;
+use std::task::Poll;
+use std::time::Duration;
+
+use chrono::{TimeDelta, Utc};
+use futures_util::{Stream, StreamExt, stream};
+use oxyde_cloud_client::{
+    Client, ClientConfig, ClientError, Result, RetryPolicy, UploadOptions, blocking, sha256_file,
+};
+use oxyde_cloud_common::config::CloudConfig;
+use oxyde_cloud_common::net::{
+    ApiToken, App, CertificateStatus, DeployManifest, Deployment, DeploymentStatus, DnsRecordKind,
+    Domain, DomainStatus, LogEntry, LogFilter, LogLevel, LogRequest, LogStreamRequest,
+    LoginResponse, ManifestResponse, NewTokenRequest, NewTokenResponse, OutputStream, Secret,
+    SecretScope, Team, TeamInvitation, TeamMember, TeamRole, TokenRestriction, TokenScope,
+};
+use oxyde_cloud_common::runtime::{Instances, Memory, RuntimeConfig};
+use oxyde_cloud_testkit::fixtures::{cloud_config, manifest_entry};
+use oxyde_cloud_testkit::{FakeCloud, StatusCode};
+
+/// Defines every test in the module `async_client` with a [`Client`] and in the module
+/// `blocking_client` with a [`Blocking`] client. The tests get the fake API and its client and
+/// can `build` other clients of the same kind.
+macro_rules! client_tests {
+    ($(#[test] async fn $name:ident($cloud:ident, $client:pat) $body:block)*) => {
+        mod async_client {
+            use super::*;
+
+            fn build(config: ClientConfig, api_key: String) -> Client {
+                config.build(api_key).unwrap()
+            }
+
+            $(
+                #[tokio::test]
+                async fn $name() {
+                    let $cloud = FakeCloud::start().await;
+                    let $client = $cloud.client();
+                    $body
+                }
+            )*
+        }
+
+        mod blocking_client {
+            use super::*;
+
+            fn build(config: ClientConfig, api_key: String) -> Blocking {
+                Blocking::new(config.build_blocking(api_key).unwrap())
+            }
+
+            $(
+                // Blocking in place needs a thread to move the fake API to.
+                #[tokio::test(flavor = "multi_thread")]
+                async fn $name() {
+                    let $cloud = FakeCloud::start().await;
+                    let $client = Blocking::new($cloud.blocking_client());
+                    $body
+                }
+            )*
+        }
+    };
+}
+
+/// A blocking client with the async methods of [`Client`], so the tests can call either. The
+/// calls block in place, which hands the other tasks of the test's runtime to other threads.
+struct Blocking(Option<blocking::Client>);
+
+impl Blocking {
+    fn new(client: blocking::Client) -> Self {
+        Self(Some(client))
+    }
+
+    fn client(&self) -> &blocking::Client {
+        self.0.as_ref().expect("only taken on drop")
+    }
+}
+
+impl Drop for Blocking {
+    fn drop(&mut self) {
+        // Shutting down the client's runtime blocks as well.
+        let client = self.0.take();
+        tokio::task::block_in_place(|| drop(client));
+    }
+}
+
+/// Generates async methods that call the blocking method of the same name.
+macro_rules! block_in_place {
+    ($(fn $name:ident(&self $(, $arg:ident: $ty:ty)*) -> $ret:ty;)*) => {
+        $(
+            async fn $name(&self $(, $arg: $ty)*) -> Result<$ret> {
+                tokio::task::block_in_place(|| self.client().$name($($arg),*))
+            }
+        )*
+    };
+}
+
+impl Blocking {
+    block_in_place! {
+        fn teams(&self) -> Vec<Team>;
+        fn new_app(&self, app_slug: &str, team_slug: &str, name: &str) -> bool;
+        fn new_team(&self, team_slug: &str) -> bool;
+        fn set_team_name(&self, team_slug: &str, team_name: &str) -> ();
+        fn login(&self) -> LoginResponse;
+        fn submit_manifest(&self, manifest: &DeployManifest) -> ManifestResponse;
+        fn upload_done(&self, config: &CloudConfig) -> String;
+        fn log(&self, request: &LogRequest) -> Vec<LogEntry>;
+
+        fn apps(&self) -> Vec<App>;
+        fn app(&self, app_slug: &str) -> App;
+        fn delete_app(&self, app_slug: &str) -> ();
+        fn transfer_app(&self, app_slug: &str, team_slug: &str) -> ();
+
+        fn deployments(&self, app_slug: &str) -> Vec<Deployment>;
+        fn deployment(&self, id: &str) -> Deployment;
+        fn rollback(&self, app_slug: &str, deployment_id: &str) -> String;
+        fn wait_for_deployment(&self, id: &str, timeout: Duration) -> Deployment;
+
+        fn add_domain(&self, app_slug: &str, domain: &str) -> Domain;
+        fn domains(&self, app_slug: &str) -> Vec<Domain>;
+        fn domain(&self, app_slug: &str, domain: &str) -> Domain;
+        fn verify_domain(&self, app_slug: &str, domain: &str) -> Domain;
+        fn remove_domain(&self, app_slug: &str, domain: &str) -> ();
+
+        fn set_secret(&self, scope: &SecretScope, name: &str, value: &str) -> ();
+        fn list_secrets(&self, scope: &SecretScope) -> Vec<Secret>;
+        fn delete_secret(&self, scope: &SecretScope, name: &str) -> ();
+        fn missing_secrets(&self, app_slug: &str, names: &[String]) -> Vec<String>;
+
+        fn team_members(&self, team_slug: &str) -> Vec<TeamMember>;
+        fn team_invitations(&self, team_slug: &str) -> Vec<TeamInvitation>;
+        fn invite_member(&self, team_slug: &str, email: &str, role: TeamRole) -> TeamInvitation;
+        fn remove_member(&self, team_slug: &str, username: &str) -> ();
+        fn set_member_role(&self, team_slug: &str, username: &str, role: TeamRole) -> ();
+
+        fn create_token(&self, request: &NewTokenRequest) -> NewTokenResponse;
+        fn list_tokens(&self) -> Vec<ApiToken>;
+        fn revoke_token(&self, id: &str) -> ();
+
+        fn upload_file(&self, app_slug: &str, path: &Path) -> ();
+        fn upload_blob_with_options(
+            &self,
+            app_slug: &str,
+            path: &Path,
+            sha256: &str,
+            options: &UploadOptions
+        ) -> ();
+    }
+
+    async fn upload_blob_reader(
+        &self,
+        app_slug: &str,
+        sha256: &str,
+        reader: impl Read + Send,
+        len: u64,
+    ) -> Result<()> {
+        tokio::task::block_in_place(|| {
+            self.client()
+                .upload_blob_reader(app_slug, sha256, reader, len)
+        })
+    }
+
+    fn log_stream(
+        &self,
+        request: &LogStreamRequest,
+    ) -> impl Stream<Item = Result<LogEntry>> + use<> {
+        let mut entries = self.client().log_stream(request);
+        stream::poll_fn(move |_| Poll::Ready(tokio::task::block_in_place(|| entries.next())))
+    }
+}
+
+client_tests! {
+    #[test]
+    async fn creates_and_names_teams(cloud, client) {
+        assert!(client.new_team("my-team").await.unwrap());
+        assert!(!client.new_team("my-team").await.unwrap());
+        client.set_team_name("my-team", "My Team").await.unwrap();
+
+        let teams = client.teams().await.unwrap();
+        assert_eq!(teams.len(), 1);
+        assert_eq!(teams[0].slug, "my-team");
+        assert_eq!(teams[0].name, "My Team");
+    }
+
+    #[test]
+    async fn creates_apps_in_existing_teams(cloud, client) {
+        cloud.state().add_team("my-team", "My Team");
+
+        assert!(client.new_app("my-app", "my-team", "My App").await.unwrap());
+        assert!(!client.new_app("my-app", "my-team", "My App").await.unwrap());
+
+        let err = client
+            .new_app("other-app", "unknown-team", "Other App")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ClientError::NotFound { .. }), "{err:?}");
+    }
+
+    #[test]
+    async fn logs_in_and_reads_logs(cloud, client) {
+        cloud.state().add_log("build", "Compiling my-app");
+
+        assert_eq!(client.login().await.unwrap().username, "tester");
+
+        let entries = client.log(&LogRequest::new("build")).await.unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].message, "Compiling my-app");
+    }
+
+    #[test]
+    async fn filters_log_entries(cloud, client) {
+        let start = Utc::now();
+        {
+            let mut state = cloud.state();
+            for (minute, level, instance, message) in [
+                (0, LogLevel::Info, "a", "Listening on port 3000"),
+                (1, LogLevel::Warn, "a", "Slow request to /api"),
+                (2, LogLevel::Error, "b", "Request to /api failed"),
+                (3, LogLevel::Error, "a", "Database unavailable"),
+            ] {
+                state.append_log_entry(
+                    "my-app",
+                    LogEntry {
+                        timestamp: start + TimeDelta::minutes(minute),
+                        level,
+                        stream: OutputStream::Stderr,
+                        instance: instance.to_string(),
+                        message: message.to_string(),
+                        cursor: None,
+                    },
+                );
+            }
+        }
+
+        let messages = |entries: Vec<LogEntry>| {
+            entries
+                .into_iter()
+                .map(|entry| entry.message)
+                .collect::<Vec<_>>()
+        };
+
+        let request = LogRequest {
+            level: Some(LogLevel::Warn),
+            filter: Some(LogFilter::Text("/api".to_string())),
+            ..LogRequest::new("my-app")
+        };
+        assert_eq!(
+            messages(client.log(&request).await.unwrap()),
+            ["Slow request to /api", "Request to /api failed"]
+        );
+
+        let request = LogRequest {
+            since: Some(start + TimeDelta::minutes(1)),
+            until: Some(start + TimeDelta::minutes(3)),
+            ..LogRequest::new("my-app")
+        };
+        assert_eq!(
+            messages(client.log(&request).await.unwrap()),
+            ["Slow request to /api", "Request to /api failed"]
+        );
+
+        let request = LogRequest {
+            instance: Some("a".to_string()),
+            filter: Some(LogFilter::Regex("^(Listening|Database)".to_string())),
+            tail: Some(1),
+            ..LogRequest::new("my-app")
+        };
+        assert_eq!(
+            messages(client.log(&request).await.unwrap()),
+            ["Database unavailable"]
+        );
+    }
+
+    #[test]
+    async fn follows_logs_across_reconnects(cloud, client) {
+        cloud.state().add_log("my-app", "one\ntwo\nthree");
+        cloud.faults().end_log_streams_after(2);
+
+        let mut entries = std::pin::pin!(client.log_stream(&LogStreamRequest::new("my-app")));
+
+        let mut received = Vec::new();
+        for _ in 0..3 {
+            received.push(entries.next().await.unwrap().unwrap().message);
+        }
+        cloud.state().append_log("my-app", "four");
+        received.push(entries.next().await.unwrap().unwrap().message);
+
+        assert_eq!(received, ["one", "two", "three", "four"]);
+        assert!(cloud.state().hits("log/stream") >= 2);
+    }
+
+    #[test]
+    async fn follows_only_matching_log_entries(cloud, client) {
+        cloud
+            .state()
+            .add_log("my-app", "GET /\nGET /api\nGET /about\nGET /api/users");
+
+        let request = LogStreamRequest {
+            tail: Some(1),
+            filter: Some(LogFilter::Text("/api".to_string())),
+            ..LogStreamRequest::new("my-app")
+        };
+        let mut entries = std::pin::pin!(client.log_stream(&request));
+
+        let first = entries.next().await.unwrap().unwrap();
+        assert_eq!(first.message, "GET /api/users");
+        cloud.state().append_log("my-app", "GET /contact");
+        cloud.state().append_log("my-app", "GET /api/teams");
+        assert_eq!(
+            entries.next().await.unwrap().unwrap().message,
+            "GET /api/teams"
+        );
+
+        // Resuming after the first entry skips the entries before it, whatever the tail.
+        let request = LogStreamRequest {
+            cursor: first.cursor,
+            ..request
+        };
+        let mut entries = std::pin::pin!(client.log_stream(&request));
+        assert_eq!(
+            entries.next().await.unwrap().unwrap().message,
+            "GET /api/teams"
+        );
+    }
+
+    #[test]
+    async fn fails_to_follow_unknown_logs(cloud, client) {
+        let mut entries = std::pin::pin!(client.log_stream(&LogStreamRequest::new("unknown")));
+
+        let err = entries.next().await.unwrap().unwrap_err();
+        assert!(matches!(err, ClientError::NotFound { .. }), "{err:?}");
+        assert!(entries.next().await.is_none());
+    }
+
+    #[test]
+    async fn rejects_invalid_api_key(cloud, _) {
+        let client = build(cloud.client_config(), "wrong".to_string());
+
+        let err = client.login().await.unwrap_err();
+        assert!(matches!(err, ClientError::Unauthorized { .. }), "{err:?}");
+    }
+
+    #[test]
+    async fn uploads_files_in_chunks(cloud, client) {
+        cloud.state().add_app("my-app", "my-team").chunk_size = Some(4);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("server");
+        std::fs::write(&path, b"0123456789").unwrap();
+
+        client.upload_file("my-app", &path).await.unwrap();
+
+        let state = cloud.state();
+        let file_name = path.to_string_lossy();
+        assert_eq!(state.file("my-app", &file_name), Some(&b"0123456789"[..]));
+        assert_eq!(state.hits("apps/upload-file"), 3);
+        assert!(state.sessions.is_empty());
+    }
+
+    #[test]
+    async fn uploads_empty_files(cloud, client) {
+        let empty = manifest_entry("site/empty.txt", b"");
+        cloud.state().add_app("my-app", "my-team");
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("empty.txt");
+        std::fs::write(&path, b"").unwrap();
+
+        client.upload_file("my-app", &path).await.unwrap();
+        client
+            .upload_blob_reader("my-app", &empty.sha256, &b""[..], 0)
+            .await
+            .unwrap();
+
+        let state = cloud.state();
+        let file_name = path.to_string_lossy();
+        assert_eq!(state.file("my-app", &file_name), Some(&b""[..]));
+        assert_eq!(state.blobs[&empty.sha256], b"");
+        assert_eq!(state.hits("apps/upload-file"), 2);
+        assert!(state.sessions.is_empty());
+    }
+
+    #[test]
+    async fn rejects_sessions_without_chunk_size(cloud, client) {
+        cloud.state().add_app("my-app", "my-team").chunk_size = Some(0);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("server");
+        std::fs::write(&path, b"0123456789").unwrap();
+
+        let err = client.upload_file("my-app", &path).await.unwrap_err();
+        assert!(
+            matches!(err, ClientError::InvalidChunkSize { .. }),
+            "{err:?}"
+        );
+        assert_eq!(cloud.state().hits("apps/upload-file"), 0);
+    }
+
+    #[test]
+    async fn deploys_only_missing_blobs(cloud, client) {
+        let unchanged = manifest_entry("site/index.html", b"<html></html>");
+        let changed = manifest_entry("server", b"new binary");
+        {
+            let mut state = cloud.state();
+            state.add_app("my-app", "my-team");
+            state
+                .blobs
+                .insert(unchanged.sha256.clone(), b"<html></html>".to_vec());
+        }
+
+        let manifest = DeployManifest {
+            app_slug: "my-app".to_string(),
+            files: vec![unchanged, changed.clone()],
+        };
+        let response = client.submit_manifest(&manifest).await.unwrap();
+        assert_eq!(response.missing_blobs, vec![changed.sha256.clone()]);
+
+        client
+            .upload_blob_reader("my-app", &changed.sha256, &b"new binary"[..], changed.size)
+            .await
+            .unwrap();
+        client.upload_done(&cloud_config("my-app")).await.unwrap();
+
+        let state = cloud.state();
+        assert_eq!(state.blobs[&changed.sha256], b"new binary");
+        assert_eq!(state.deployments.len(), 1);
+    }
+
+    #[test]
+    async fn sends_runtime_settings_with_upload_done(cloud, client) {
+        cloud.state().add_app("my-app", "my-team");
+
+        let runtime = RuntimeConfig {
+            instances: Instances { min: 0, max: 3 },
+            memory: Some(Memory { megabytes: 512 }),
+            regions: vec!["fra".to_string()],
+            sleep_when_idle: Some(Duration::from_secs(15 * 60)),
+            ..Default::default()
+        };
+        let config = CloudConfig {
+            runtime: Some(runtime.clone()),
+            ..cloud_config("my-app")
+        };
+        client.upload_done(&config).await.unwrap();
+        assert_eq!(cloud.state().runtimes["my-app"], runtime);
+
+        let config = CloudConfig {
+            runtime: Some(RuntimeConfig {
+                instances: Instances { min: 2, max: 1 },
+                ..Default::default()
+            }),
+            ..cloud_config("my-app")
+        };
+        let err = client.upload_done(&config).await.unwrap_err();
+        assert_eq!(err.status(), Some(StatusCode::BAD_REQUEST));
+    }
+
+    #[test]
+    async fn lists_deployments_newest_first(cloud, client) {
+        cloud.state().add_app("my-app", "my-team");
+
+        client.upload_done(&cloud_config("my-app")).await.unwrap();
+        client.upload_done(&cloud_config("my-app")).await.unwrap();
+
+        let deployments = client.deployments("my-app").await.unwrap();
+        assert_eq!(deployments.len(), 2);
+        assert!(deployments[0].created_at >= deployments[1].created_at);
+        assert_eq!(deployments[0].status, DeploymentStatus::Success);
+        assert_eq!(deployments[0].creator, "tester");
+
+        let deployment = client.deployment(&deployments[1].id).await.unwrap();
+        assert_eq!(deployment, deployments[1]);
+
+        let err = client.deployment("unknown").await.unwrap_err();
+        assert!(matches!(err, ClientError::NotFound { .. }), "{err:?}");
+    }
+
+    #[test]
+    async fn escapes_values_in_routes(cloud, client) {
+        cloud.state().add_app("odd/app #1?", "my-team");
+
+        client
+            .upload_done(&cloud_config("odd/app #1?"))
+            .await
+            .unwrap();
+
+        let deployments = client.deployments("odd/app #1?").await.unwrap();
+        assert_eq!(deployments.len(), 1);
+        assert_eq!(client.app("odd/app #1?").await.unwrap().slug, "odd/app #1?");
+
+        let err = client.deployment("../apps/odd").await.unwrap_err();
+        assert!(matches!(err, ClientError::NotFound { .. }), "{err:?}");
+    }
+
+    #[test]
+    async fn waits_for_pending_deployments(cloud, client) {
+        {
+            let mut state = cloud.state();
+            state.add_app("my-app", "my-team");
+            state.deployment_pending_polls = 2;
+        }
+
+        let id = client.upload_done(&cloud_config("my-app")).await.unwrap();
+        let deployment = client
+            .wait_for_deployment(&id, Duration::from_secs(10))
+            .await
+            .unwrap();
+
+        assert_eq!(deployment.status, DeploymentStatus::Success);
+        assert_eq!(cloud.state().hits(&format!("deployments/{id}")), 3);
+    }
+
+    #[test]
+    async fn reports_startup_log_of_failed_deployments(cloud, client) {
+        {
+            let mut state = cloud.state();
+            state.add_app("my-app", "my-team");
+            state.deployment_outcome = DeploymentStatus::Failure;
+            state.deployment_pending_polls = 1;
+        }
+
+        let id = client.upload_done(&cloud_config("my-app")).await.unwrap();
+        cloud
+            .state()
+            .append_log("my-app", "Error: DATABASE_URL is not set");
+
+        let err = client
+            .wait_for_deployment(&id, Duration::from_secs(10))
+            .await
+            .unwrap_err();
+
+        let ClientError::DeploymentFailed {
+            deployment,
+            startup_log,
+        } = err
+        else {
+            panic!("unexpected error: {err:?}");
+        };
+        assert_eq!(deployment.id, id);
+        assert_eq!(startup_log.len(), 1);
+        assert_eq!(startup_log[0].message, "Error: DATABASE_URL is not set");
+    }
+
+    #[test]
+    async fn times_out_waiting_for_deployments(cloud, client) {
+        {
+            let mut state = cloud.state();
+            state.add_app("my-app", "my-team");
+            state.deployment_pending_polls = usize::MAX;
+        }
+
+        let id = client.upload_done(&cloud_config("my-app")).await.unwrap();
+        let err = client
+            .wait_for_deployment(&id, Duration::from_millis(600))
+            .await
+            .unwrap_err();
+
+        assert!(
+            matches!(err, ClientError::DeploymentTimeout { .. }),
+            "{err:?}"
+        );
+    }
+
+    #[test]
+    async fn rolls_back_to_successful_deployments(cloud, client) {
+        let (good, bad) = {
+            let mut state = cloud.state();
+            state.add_app("my-app", "my-team");
+            let good = state.add_deployment("my-app", 100, DeploymentStatus::Success);
+            let bad = state.add_deployment("my-app", 200, DeploymentStatus::Failure);
+            (good, bad)
+        };
+
+        let id = client.rollback("my-app", &good).await.unwrap();
+        let deployment = client
+            .wait_for_deployment(&id, Duration::from_secs(10))
+            .await
+            .unwrap();
+        assert_eq!(deployment.size, 100);
+        assert_eq!(deployment.rolled_back_from.as_deref(), Some(good.as_str()));
+        assert_eq!(client.deployments("my-app").await.unwrap()[0].id, id);
+
+        let err = client.rollback("my-app", &bad).await.unwrap_err();
+        assert!(matches!(err, ClientError::Conflict { .. }), "{err:?}");
+    }
+
+    #[test]
+    async fn manages_secrets_per_scope(cloud, client) {
+        cloud.state().add_app("my-app", "my-team");
+
+        let app = SecretScope::App("my-app".to_string());
+        let team = SecretScope::Team("my-team".to_string());
+
+        client
+            .set_secret(&app, "DATABASE_URL", "postgres://old")
+            .await
+            .unwrap();
+        client
+            .set_secret(&app, "DATABASE_URL", "postgres://new")
+            .await
+            .unwrap();
+        client
+            .set_secret(&team, "API_TOKEN", "token")
+            .await
+            .unwrap();
+
+        let secrets = client.list_secrets(&app).await.unwrap();
+        assert_eq!(secrets.len(), 1);
+        assert_eq!(secrets[0].name, "DATABASE_URL");
+        assert_eq!(
+            cloud.state().secret(&app, "DATABASE_URL"),
+            Some("postgres://new")
+        );
+
+        client.delete_secret(&app, "DATABASE_URL").await.unwrap();
+        assert!(client.list_secrets(&app).await.unwrap().is_empty());
+        assert_eq!(client.list_secrets(&team).await.unwrap().len(), 1);
+
+        let err = client
+            .delete_secret(&app, "DATABASE_URL")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ClientError::NotFound { .. }), "{err:?}");
+
+        let err = client.set_secret(&app, "NOT-VALID", "x").await.unwrap_err();
+        assert_eq!(err.status(), Some(StatusCode::BAD_REQUEST));
+    }
+
+    #[test]
+    async fn reports_missing_secrets(cloud, client) {
+        cloud
+            .state()
+            .add_app("my-app", "my-team")
+            .set_secret(SecretScope::App("my-app".to_string()), "DB", "postgres://")
+            .set_secret(SecretScope::Team("my-team".to_string()), "TOKEN", "token");
+
+        let names = ["DB", "TOKEN", "MISSING"].map(str::to_string);
+        let missing = client.missing_secrets("my-app", &names).await.unwrap();
+        assert_eq!(missing, ["MISSING"]);
+    }
+
+    #[test]
+    async fn manages_apps(cloud, client) {
+        cloud.state().add_team("my-team", "My Team");
+        cloud.state().add_team("other-team", "Other Team");
+
+        assert!(client.new_app("my-app", "my-team", "My App").await.unwrap());
+        assert!(
+            client
+                .new_app("old-app", "my-team", "Old App")
+                .await
+                .unwrap()
+        );
+        client
+            .set_secret(&SecretScope::App("old-app".to_string()), "DB", "x")
+            .await
+            .unwrap();
+
+        let apps = client.apps().await.unwrap();
+        assert_eq!(
+            apps.iter().map(|app| app.slug.as_str()).collect::<Vec<_>>(),
+            ["my-app", "old-app"]
+        );
+
+        let app = client.app("my-app").await.unwrap();
+        assert_eq!(app.name, "My App");
+        assert_eq!(app.team_slug, "my-team");
+
+        client.transfer_app("my-app", "other-team").await.unwrap();
+        assert_eq!(client.app("my-app").await.unwrap().team_slug, "other-team");
+
+        let err = client.transfer_app("my-app", "no-team").await.unwrap_err();
+        assert!(matches!(err, ClientError::NotFound { .. }), "{err:?}");
+
+        client.delete_app("old-app").await.unwrap();
+        let err = client.app("old-app").await.unwrap_err();
+        assert!(matches!(err, ClientError::NotFound { .. }), "{err:?}");
+        assert!(cloud.state().secrets.is_empty());
+    }
+
+    #[test]
+    async fn manages_team_members(cloud, client) {
+        assert!(client.new_team("my-team").await.unwrap());
+        cloud
+            .state()
+            .add_member("my-team", "alice", TeamRole::Deployer);
+
+        let members = client.team_members("my-team").await.unwrap();
+        assert_eq!(
+            members
+                .iter()
+                .map(|m| (m.username.as_str(), m.role))
+                .collect::<Vec<_>>(),
+            [("alice", TeamRole::Deployer), ("tester", TeamRole::Owner)]
+        );
+
+        let invitation = client
+            .invite_member("my-team", "bob@example.com", TeamRole::Viewer)
+            .await
+            .unwrap();
+        assert_eq!(invitation.invited_by, "tester");
+        assert_eq!(
+            client.team_invitations("my-team").await.unwrap(),
+            [invitation]
+        );
+        let err = client
+            .invite_member("my-team", "bob@example.com", TeamRole::Admin)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ClientError::Conflict { .. }), "{err:?}");
+
+        let err = client
+            .set_member_role("my-team", "tester", TeamRole::Admin)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ClientError::Conflict { .. }), "{err:?}");
+
+        client
+            .set_member_role("my-team", "alice", TeamRole::Owner)
+            .await
+            .unwrap();
+        client.remove_member("my-team", "tester").await.unwrap();
+
+        let members = client.team_members("my-team").await.unwrap();
+        assert_eq!(members.len(), 1);
+        assert_eq!(members[0].role, TeamRole::Owner);
+
+        let err = client.remove_member("my-team", "tester").await.unwrap_err();
+        assert!(matches!(err, ClientError::NotFound { .. }), "{err:?}");
+    }
+
+    #[test]
+    async fn authenticates_with_created_tokens(cloud, client) {
+        cloud.state().add_app("my-app", "my-team");
+
+        let request = NewTokenRequest {
+            restriction: Some(TokenRestriction::App("my-app".to_string())),
+            ..NewTokenRequest::new("github-ci", [TokenScope::Deploy])
+        };
+        let created = client.create_token(&request).await.unwrap();
+        assert_eq!(created.token.scopes, [TokenScope::Deploy]);
+
+        let ci_client = build(cloud.client_config(), created.secret);
+        ci_client.deployments("my-app").await.unwrap();
+
+        let tokens = client.list_tokens().await.unwrap();
+        assert_eq!(tokens.len(), 1);
+        assert_eq!(tokens[0].name, "github-ci");
+        assert!(tokens[0].last_used_at.is_some());
+
+        client.revoke_token(&created.token.id).await.unwrap();
+        let err = ci_client.deployments("my-app").await.unwrap_err();
+        assert!(matches!(err, ClientError::Unauthorized { .. }), "{err:?}");
+
+        let expired = NewTokenRequest {
+            expires_at: Some(Utc::now() - TimeDelta::seconds(1)),
+            ..NewTokenRequest::new("expired", [TokenScope::Read])
+        };
+        let expired = client.create_token(&expired).await.unwrap();
+        let err = build(cloud.client_config(), expired.secret)
+            .teams()
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ClientError::Unauthorized { .. }), "{err:?}");
+    }
+
+    #[test]
+    async fn verifies_custom_domains(cloud, client) {
+        cloud.state().add_app("my-app", "my-team");
+
+        let domain = client
+            .add_domain("my-app", "www.example.com")
+            .await
+            .unwrap();
+        assert_eq!(domain.status, DomainStatus::Pending);
+        assert_eq!(domain.certificate, CertificateStatus::None);
+        assert_eq!(
+            domain.records.iter().map(|r| r.kind).collect::<Vec<_>>(),
+            [DnsRecordKind::Txt, DnsRecordKind::Cname]
+        );
+
+        let err = client
+            .add_domain("my-app", "www.example.com")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ClientError::Conflict { .. }), "{err:?}");
+        let err = client
+            .add_domain("my-app", "Not A Domain")
+            .await
+            .unwrap_err();
+        assert_eq!(err.status(), Some(StatusCode::BAD_REQUEST));
+
+        let domain = client
+            .verify_domain("my-app", "www.example.com")
+            .await
+            .unwrap();
+        assert_eq!(domain.status, DomainStatus::Pending);
+
+        cloud.state().dns.insert("www.example.com".to_string());
+        let domain = client
+            .verify_domain("my-app", "www.example.com")
+            .await
+            .unwrap();
+        assert_eq!(domain.status, DomainStatus::Verified);
+        assert_eq!(
+            client
+                .domain("my-app", "www.example.com")
+                .await
+                .unwrap()
+                .certificate,
+            CertificateStatus::Issued
+        );
+
+        client
+            .remove_domain("my-app", "www.example.com")
+            .await
+            .unwrap();
+        assert!(client.domains("my-app").await.unwrap().is_empty());
+    }
+
+    #[test]
+    async fn retries_injected_server_errors(cloud, client) {
+        cloud
+            .faults()
+            .fail_next_n("teams", StatusCode::SERVICE_UNAVAILABLE, 2);
+
+        let teams = client.teams().await.unwrap();
+
+        assert!(teams.is_empty());
+        assert_eq!(cloud.state().hits("teams"), 3);
+    }
+
+    #[test]
+    async fn reuploads_dropped_chunks(cloud, client) {
+        cloud.state().add_app("my-app", "my-team").chunk_size = Some(4);
+        cloud.faults().drop_next_chunks(1);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("server");
+        std::fs::write(&path, b"0123456789").unwrap();
+        let sha256 = sha256_file(&path).await.unwrap();
+
+        let manifest = DeployManifest {
+            app_slug: "my-app".to_string(),
+            files: vec![manifest_entry("server", b"0123456789")],
+        };
+        client.submit_manifest(&manifest).await.unwrap();
+
+        let options = UploadOptions::default();
+        client
+            .upload_blob_with_options("my-app", &path, &sha256, &options)
+            .await
+            .unwrap();
+
+        let err = client
+            .upload_done(&cloud_config("my-app"))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ClientError::Conflict { .. }), "{err:?}");
+
+        // Deploying again finds the blob still missing and uploads it once more.
+        let response = client.submit_manifest(&manifest).await.unwrap();
+        assert_eq!(response.missing_blobs, vec![sha256.clone()]);
+        client
+            .upload_blob_with_options("my-app", &path, &sha256, &options)
+            .await
+            .unwrap();
+        client.upload_done(&cloud_config("my-app")).await.unwrap();
+
+        assert_eq!(cloud.state().blobs[&sha256], b"0123456789");
+    }
+
+    #[test]
+    async fn times_out_on_latency(cloud, _) {
+        cloud.faults().latency = Some(Duration::from_millis(500));
+
+        let config = cloud
+            .client_config()
+            .timeout(Duration::from_millis(50))
+            .retry_policy(RetryPolicy::none());
+        let client = build(config, cloud.api_key().to_string());
+
+        let err = client.teams().await.unwrap_err();
+        assert!(matches!(err, ClientError::Transport { .. }), "{err:?}");
+    }
+}